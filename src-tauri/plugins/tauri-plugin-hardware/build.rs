@@ -1,4 +1,14 @@
-const COMMANDS: &[&str] = &["get_system_info", "get_system_usage"];
+const COMMANDS: &[&str] = &[
+    "get_system_info",
+    "refresh_system_info",
+    "get_system_usage",
+    "get_cpu_temperature",
+    "get_power_status",
+    "get_gpu_process_memory",
+    "run_hardware_diagnostics",
+    "start_usage_stream",
+    "stop_usage_stream",
+];
 
 fn main() {
     tauri_plugin::Builder::new(COMMANDS).build();
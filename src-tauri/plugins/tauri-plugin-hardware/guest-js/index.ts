@@ -1,21 +1,44 @@
 import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 
 // Types
 export interface CpuStaticInfo {
   name: string;
   core_count: number;
+  logical_cores: number;
+  performance_cores: number;
+  efficiency_cores: number;
+  base_frequency_mhz: number;
   arch: string;
   extensions: string[];
 }
 
+export interface CpuTemperature {
+  label: string;
+  temperature: number | null;
+}
+
 export interface GpuInfo {
+  index: number;
   name: string;
   total_memory: number;
   vendor: string;
   uuid: string;
-  driver_version: string;
+  driver_version: string | null;
+  compute_runtime_version: string | null;
   nvidia_info?: any;
   vulkan_info?: any;
+  is_integrated: boolean;
+  is_discrete: boolean;
+  is_unified: boolean;
+  unified_memory_total_mb: number | null;
+  recommended_working_set_mb: number | null;
+}
+
+export interface NpuInfo {
+  name: string;
+  vendor: string;
+  tops: number | null;
 }
 
 export interface SystemInfo {
@@ -24,26 +47,126 @@ export interface SystemInfo {
   os_name: string;
   total_memory: number;
   gpus: GpuInfo[];
+  npus: NpuInfo[];
 }
 
 export interface GpuUsage {
   uuid: string;
   used_memory: number;
   total_memory: number;
+  power_watts: number | null;
+  core_clock_mhz: number | null;
+  mem_clock_mhz: number | null;
+  gpu_utilization: number | null;
+}
+
+export type MemoryPressure = 'normal' | 'warning' | 'critical';
+
+export interface MemoryBreakdown {
+  available_mb: number | null;
+  cached_mb: number | null;
+  buffers_mb: number | null;
+  wired_mb: number | null;
+  compressed_mb: number | null;
 }
 
 export interface SystemUsage {
   cpu: number;
+  per_core: number[];
   used_memory: number;
   total_memory: number;
+  swap_used_mb: number | null;
+  swap_total_mb: number | null;
+  memory_pressure: MemoryPressure | null;
+  memory_breakdown: MemoryBreakdown;
+  throttling: ThrottlingStatus;
+  net_rx_bytes_per_sec: number;
+  net_tx_bytes_per_sec: number;
+  disk_read_bytes_per_sec: number;
+  disk_write_bytes_per_sec: number;
   gpus: GpuUsage[];
 }
 
+export interface ThrottlingStatus {
+  cpu_throttled: boolean;
+  gpu_throttled: boolean | null;
+}
+
+export type BatteryState =
+  | 'charging'
+  | 'discharging'
+  | 'full'
+  | 'not_charging'
+  | 'unknown';
+
+export interface BatteryStatus {
+  percentage: number | null;
+  state: BatteryState;
+}
+
+export interface PowerStatus {
+  ac_connected: boolean | null;
+  battery: BatteryStatus | null;
+}
+
+export interface SubsystemDiagnostic {
+  subsystem: string;
+  ok: boolean;
+  error: string | null;
+  backend: string | null;
+  values: unknown;
+}
+
+export interface HardwareDiagnostics {
+  plugin_version: string;
+  os_name: string;
+  kernel_version: string | null;
+  subsystems: SubsystemDiagnostic[];
+}
+
 // Hardware commands
 export async function getSystemInfo(): Promise<SystemInfo> {
   return await invoke('plugin:hardware|get_system_info');
 }
 
+export async function refreshSystemInfo(): Promise<SystemInfo> {
+  return await invoke('plugin:hardware|refresh_system_info');
+}
+
 export async function getSystemUsage(): Promise<SystemUsage> {
   return await invoke('plugin:hardware|get_system_usage');
 }
+
+export async function getCpuTemperature(): Promise<CpuTemperature[]> {
+  return await invoke('plugin:hardware|get_cpu_temperature');
+}
+
+export async function getPowerStatus(): Promise<PowerStatus> {
+  return await invoke('plugin:hardware|get_power_status');
+}
+
+// Map of PID to GPU memory used in bytes, empty without per-process accounting (non-NVIDIA)
+export async function getGpuProcessMemory(): Promise<Record<number, number>> {
+  return await invoke('plugin:hardware|get_gpu_process_memory');
+}
+
+export async function runHardwareDiagnostics(): Promise<HardwareDiagnostics> {
+  return await invoke('plugin:hardware|run_hardware_diagnostics');
+}
+
+// Live usage stream
+export async function startUsageStream(intervalMs: number): Promise<void> {
+  return await invoke('plugin:hardware|start_usage_stream', { intervalMs });
+}
+
+export async function stopUsageStream(): Promise<void> {
+  return await invoke('plugin:hardware|stop_usage_stream');
+}
+
+export async function onSystemUsage(
+  handler: (usage: SystemUsage) => void
+): Promise<UnlistenFn> {
+  return await listen<SystemUsage>('hardware://usage', (event) =>
+    handler(event.payload)
+  );
+}
@@ -1,86 +1,169 @@
 use crate::{
+    constants::{SYSTEM_INFO_CACHE_TTL_SECS, USAGE_STREAM_EVENT, USAGE_STREAM_MIN_INTERVAL_MS},
+    cpu::{get_cpu_temperatures, is_cpu_throttled, sample_cpu_usage},
+    diagnostics, disk,
     helpers::get_jan_libvulkan_path,
-    types::{CpuStaticInfo, SystemInfo, SystemUsage},
-    vendor::{nvidia, vulkan},
+    memory::{get_memory_breakdown, get_memory_pressure, get_swap_usage},
+    network, power,
+    state::HardwareState,
+    types::{
+        CpuStaticInfo, CpuTemperature, GpuInfo, HardwareDiagnostics, PowerStatus, SystemInfo,
+        SystemUsage, ThrottlingStatus, Vendor,
+    },
+    vendor::{apple, npu, nvidia, vulkan},
     SYSTEM_INFO,
 };
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use sysinfo::System;
-use tauri::Runtime;
+use tauri::{Emitter, Manager, Runtime, State};
 
-#[tauri::command]
-pub fn get_system_info<R: Runtime>(app: tauri::AppHandle<R>) -> SystemInfo {
-    SYSTEM_INFO
-        .get_or_init(|| {
-            let mut system = System::new();
-            system.refresh_memory();
-
-            let mut gpu_map = std::collections::HashMap::new();
-            for gpu in nvidia::get_nvidia_gpus() {
-                gpu_map.insert(gpu.uuid.clone(), gpu);
-            }
+/// Cached `SystemInfo` and when its volatile fields were last probed
+pub(crate) struct SystemInfoCache {
+    info: SystemInfo,
+    probed_at: Instant,
+}
 
-            // try system vulkan first
-            let paths = vec!["".to_string(), get_jan_libvulkan_path(app.clone())];
-            let mut vulkan_gpus = vec![];
-            for path in paths {
-                vulkan_gpus = vulkan::get_vulkan_gpus(&path);
-                if !vulkan_gpus.is_empty() {
-                    break;
-                }
-            }
+pub(crate) fn probe_gpus<R: Runtime>(app: &tauri::AppHandle<R>) -> Vec<GpuInfo> {
+    // keep GPUs in a stable order so that get_system_usage reports them
+    // at the same indices: NVML devices first, then Metal, then Vulkan-only
+    let mut gpus = nvidia::get_nvidia_gpus();
+    gpus.extend(apple::get_apple_gpus());
 
-            for gpu in vulkan_gpus {
-                match gpu_map.get_mut(&gpu.uuid) {
-                    // for existing NVIDIA GPUs, add Vulkan info
-                    Some(nvidia_gpu) => {
-                        nvidia_gpu.vulkan_info = gpu.vulkan_info;
-                    }
-                    None => {
-                        gpu_map.insert(gpu.uuid.clone(), gpu);
-                    }
-                }
-            }
+    // try system vulkan first
+    let paths = vec!["".to_string(), get_jan_libvulkan_path(app.clone())];
+    let mut vulkan_gpus = vec![];
+    for path in paths {
+        vulkan_gpus = vulkan::get_vulkan_gpus(&path);
+        if !vulkan_gpus.is_empty() {
+            break;
+        }
+    }
 
-            let os_type = if cfg!(target_os = "windows") {
-                "windows"
-            } else if cfg!(target_os = "macos") {
-                "macos"
-            } else if cfg!(target_os = "linux") {
-                "linux"
-            } else {
-                "unknown"
-            };
-            let os_name = System::long_os_version().unwrap_or("Unknown".to_string());
-
-            SystemInfo {
-                cpu: CpuStaticInfo::new(),
-                os_type: os_type.to_string(),
-                os_name,
-                total_memory: system.total_memory() / 1024 / 1024, // bytes to MiB
-                gpus: gpu_map.into_values().collect(),
+    for gpu in vulkan_gpus {
+        // MoltenVK reports the Metal device with a different UUID
+        let existing = gpus.iter_mut().find(|g| {
+            g.uuid == gpu.uuid || matches!((&g.vendor, &gpu.vendor), (Vendor::Apple, Vendor::Apple))
+        });
+        match existing {
+            // for GPUs already found via NVML or Metal, add Vulkan info
+            Some(existing_gpu) => {
+                existing_gpu.vulkan_info = gpu.vulkan_info;
             }
-        })
-        .clone()
+            None => gpus.push(gpu),
+        }
+    }
+
+    for (index, gpu) in gpus.iter_mut().enumerate() {
+        gpu.index = index;
+        gpu.is_discrete = gpu.detect_discrete();
+    }
+
+    gpus
 }
 
-#[tauri::command]
-pub fn get_system_usage<R: Runtime>(app: tauri::AppHandle<R>) -> SystemUsage {
+fn probe_system_info<R: Runtime>(app: &tauri::AppHandle<R>) -> SystemInfo {
     let mut system = System::new();
     system.refresh_memory();
 
-    // need to refresh 2 times to get CPU usage
-    system.refresh_cpu_all();
-    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
-    system.refresh_cpu_all();
+    let os_type = if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "macos"
+    } else if cfg!(target_os = "linux") {
+        "linux"
+    } else {
+        "unknown"
+    };
+    let os_name = System::long_os_version().unwrap_or("Unknown".to_string());
+
+    SystemInfo {
+        cpu: CpuStaticInfo::new(),
+        os_type: os_type.to_string(),
+        os_name,
+        total_memory: system.total_memory() / 1024 / 1024, // bytes to MiB
+        gpus: probe_gpus(app),
+        npus: npu::get_npus(),
+    }
+}
+
+/// Returns the cached system info, probing the hardware on first use.
+///
+/// `gpus` is volatile (drivers can be updated and eGPUs plugged in while Jan is
+/// running) and is re-probed once the cache is older than `SYSTEM_INFO_CACHE_TTL_SECS`.
+/// `cpu`, `os_type`, `os_name`, `total_memory` and `npus` are probed once and kept
+/// until `refresh_system_info` is called.
+#[tauri::command]
+pub fn get_system_info<R: Runtime>(app: tauri::AppHandle<R>) -> SystemInfo {
+    let mut cache = SYSTEM_INFO.lock().unwrap_or_else(|e| e.into_inner());
+    match cache.as_mut() {
+        Some(cached) => {
+            if cached.probed_at.elapsed() >= Duration::from_secs(SYSTEM_INFO_CACHE_TTL_SECS) {
+                cached.info.gpus = probe_gpus(&app);
+                cached.probed_at = Instant::now();
+            }
+            cached.info.clone()
+        }
+        None => {
+            let info = probe_system_info(&app);
+            *cache = Some(SystemInfoCache {
+                info: info.clone(),
+                probed_at: Instant::now(),
+            });
+            info
+        }
+    }
+}
+
+/// Re-probes all system info fields, replacing the cached value.
+#[tauri::command]
+pub fn refresh_system_info<R: Runtime>(app: tauri::AppHandle<R>) -> SystemInfo {
+    let mut cache = SYSTEM_INFO.lock().unwrap_or_else(|e| e.into_inner());
+    let info = probe_system_info(&app);
+    *cache = Some(SystemInfoCache {
+        info: info.clone(),
+        probed_at: Instant::now(),
+    });
+    info
+}
+
+#[tauri::command]
+pub async fn get_system_usage<R: Runtime>(app: tauri::AppHandle<R>) -> SystemUsage {
+    // CPU sampling sleeps between refreshes, run it on a blocking thread
+    let (cpu_usage, per_core) = tauri::async_runtime::spawn_blocking(sample_cpu_usage)
+        .await
+        .unwrap_or_default();
 
-    let cpus = system.cpus();
-    let cpu_usage =
-        cpus.iter().map(|cpu| cpu.cpu_usage()).sum::<f32>() / (cpus.len().max(1) as f32);
+    let mut system = System::new();
+    system.refresh_memory();
+    let (swap_used_mb, swap_total_mb) = get_swap_usage(&system);
+    let (net_rx_bytes_per_sec, net_tx_bytes_per_sec) = app
+        .try_state::<HardwareState>()
+        .map(|state| network::sample_network_rates(&state.network_sample))
+        .unwrap_or_default();
+    let (disk_read_bytes_per_sec, disk_write_bytes_per_sec) = app
+        .try_state::<HardwareState>()
+        .map(|state| disk::sample_disk_rates(&state.disk_sample))
+        .unwrap_or_default();
 
+    let cpu_throttled = is_cpu_throttled(&per_core);
     SystemUsage {
         cpu: cpu_usage,
+        per_core,
         used_memory: system.used_memory() / 1024 / 1024, // bytes to MiB,
         total_memory: system.total_memory() / 1024 / 1024, // bytes to MiB,
+        swap_used_mb,
+        swap_total_mb,
+        memory_pressure: get_memory_pressure(),
+        memory_breakdown: get_memory_breakdown(&system),
+        throttling: ThrottlingStatus {
+            cpu_throttled,
+            gpu_throttled: nvidia::is_nvidia_throttled(),
+        },
+        net_rx_bytes_per_sec,
+        net_tx_bytes_per_sec,
+        disk_read_bytes_per_sec,
+        disk_write_bytes_per_sec,
         gpus: get_system_info(app.clone())
             .gpus
             .iter()
@@ -88,3 +171,77 @@ pub fn get_system_usage<R: Runtime>(app: tauri::AppHandle<R>) -> SystemUsage {
             .collect(),
     }
 }
+
+#[tauri::command]
+pub fn get_cpu_temperature() -> Vec<CpuTemperature> {
+    get_cpu_temperatures()
+}
+
+/// Returns the AC power and battery status, so the frontend can warn before running
+/// a heavy model on battery. `battery` is `None` on desktops.
+#[tauri::command]
+pub fn get_power_status() -> PowerStatus {
+    power::get_power_status()
+}
+
+/// Returns the GPU memory used by each process, as a map of PID to bytes, so the
+/// frontend can tell how much VRAM its own backends use next to other apps.
+/// Only NVIDIA GPUs report per-process usage; the map is empty elsewhere.
+#[tauri::command]
+pub fn get_gpu_process_memory() -> HashMap<u32, u64> {
+    nvidia::get_nvidia_process_memory()
+}
+
+/// Probes the CPU, memory, each GPU and any NPU in one go and returns what was found,
+/// for users to paste into bug reports. Each subsystem reports its own status, the
+/// backend used and the raw values, and a failing probe doesn't stop the others.
+#[tauri::command]
+pub async fn run_hardware_diagnostics<R: Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<HardwareDiagnostics, String> {
+    // CPU sampling sleeps and GPU probing loads vendor libraries, keep them off the main thread
+    tauri::async_runtime::spawn_blocking(move || diagnostics::run_diagnostics(&app))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Starts emitting `hardware://usage` events every `interval_ms` milliseconds.
+/// The payload has the same shape as `get_system_usage`. Calling this again
+/// replaces the running stream with one at the new interval.
+#[tauri::command]
+pub fn start_usage_stream<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    state: State<'_, HardwareState>,
+    interval_ms: u64,
+) -> Result<(), String> {
+    let interval = Duration::from_millis(interval_ms.max(USAGE_STREAM_MIN_INTERVAL_MS));
+    let mut usage_stream = state.usage_stream.lock().map_err(|e| e.to_string())?;
+
+    if let Some(handle) = usage_stream.take() {
+        handle.abort();
+    }
+
+    *usage_stream = Some(tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let usage = get_system_usage(app.clone()).await;
+            if let Err(e) = app.emit(USAGE_STREAM_EVENT, usage) {
+                log::error!("Failed to emit {} event: {}", USAGE_STREAM_EVENT, e);
+            }
+        }
+    }));
+
+    Ok(())
+}
+
+/// Stops the stream started by `start_usage_stream`. Does nothing if no stream is running.
+#[tauri::command]
+pub fn stop_usage_stream(state: State<'_, HardwareState>) -> Result<(), String> {
+    let mut usage_stream = state.usage_stream.lock().map_err(|e| e.to_string())?;
+    if let Some(handle) = usage_stream.take() {
+        handle.abort();
+    }
+    Ok(())
+}
@@ -2,3 +2,14 @@
 pub const VENDOR_ID_AMD: u32 = 0x1002;
 pub const VENDOR_ID_NVIDIA: u32 = 0x10DE;
 pub const VENDOR_ID_INTEL: u32 = 0x8086;
+pub const VENDOR_ID_QUALCOMM: u32 = 0x17CB;
+pub const VENDOR_ID_APPLE: u32 = 0x106B;
+
+pub const USAGE_STREAM_EVENT: &str = "hardware://usage";
+pub const USAGE_STREAM_MIN_INTERVAL_MS: u64 = 500;
+
+pub const SYSTEM_INFO_CACHE_TTL_SECS: u64 = 300;
+
+// busy cores running below this fraction of their base frequency are considered throttled
+pub const CPU_THROTTLE_MIN_USAGE: f32 = 50.0;
+pub const CPU_THROTTLE_FREQUENCY_RATIO: f32 = 0.9;
@@ -0,0 +1,41 @@
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// A reading of two cumulative byte counters, such as bytes received and sent
+pub trait CounterSample: Copy {
+    fn counters(&self) -> (u64, u64);
+    fn taken_at(&self) -> Instant;
+}
+
+/// Returns how fast both counters grew between two samples, in bytes per second. Zero
+/// without a previous sample, and for a counter that went backwards (the device it
+/// counted for disappeared).
+pub fn compute_counter_rates<S: CounterSample>(previous: Option<&S>, current: &S) -> (f64, f64) {
+    let Some(previous) = previous else {
+        return (0.0, 0.0);
+    };
+    let elapsed = current
+        .taken_at()
+        .saturating_duration_since(previous.taken_at())
+        .as_secs_f64();
+    if elapsed <= 0.0 {
+        return (0.0, 0.0);
+    }
+    let rate = |before: u64, after: u64| after.saturating_sub(before) as f64 / elapsed;
+    let (before, after) = (previous.counters(), current.counters());
+    (rate(before.0, after.0), rate(before.1, after.1))
+}
+
+/// Takes a new sample with `read` and returns the rates since the one stored in
+/// `last_sample`, which is then replaced. Rates are therefore averaged over the time
+/// between calls.
+pub fn sample_counter_rates<S: CounterSample>(
+    last_sample: &Mutex<Option<S>>,
+    read: impl FnOnce() -> S,
+) -> (f64, f64) {
+    let current = read();
+    let mut last_sample = last_sample.lock().unwrap_or_else(|e| e.into_inner());
+    let rates = compute_counter_rates(last_sample.as_ref(), &current);
+    *last_sample = Some(current);
+    rates
+}
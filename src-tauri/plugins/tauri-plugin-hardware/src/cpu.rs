@@ -1,6 +1,417 @@
-use sysinfo::System;
+use sysinfo::{Components, System};
 
-use crate::types::CpuStaticInfo;
+use crate::types::{CpuStaticInfo, CpuTemperature};
+
+// Substrings of sensor labels that belong to the CPU. sysinfo reads hwmon on Linux
+// (labels are prefixed with the driver name, e.g. "coretemp Package id 0"), the SMC
+// on macOS and WMI on Windows.
+const CPU_SENSOR_KEYWORDS: &[&str] = &[
+    "coretemp", "k10temp", "zenpower", "cpu", "package", "tctl", "tdie", "core",
+];
+
+pub fn is_cpu_sensor(label: &str) -> bool {
+    let label = label.to_lowercase();
+    CPU_SENSOR_KEYWORDS
+        .iter()
+        .any(|keyword| label.contains(keyword))
+}
+
+/// Reads the temperature of every CPU sensor that can be found.
+/// Returns an empty list when no sensor is readable.
+pub fn get_cpu_temperatures() -> Vec<CpuTemperature> {
+    Components::new_with_refreshed_list()
+        .list()
+        .iter()
+        .filter(|component| is_cpu_sensor(component.label()))
+        .map(|component| CpuTemperature {
+            label: component.label().to_string(),
+            temperature: component.temperature().filter(|t| t.is_finite()),
+        })
+        .collect()
+}
+
+/// Samples CPU usage and returns the aggregate usage together with the usage of
+/// each logical core, ordered by core index so the order is stable across calls.
+///
+/// This blocks for `sysinfo::MINIMUM_CPU_UPDATE_INTERVAL`, so it should not be
+/// called from the main thread.
+pub fn sample_cpu_usage() -> (f32, Vec<f32>) {
+    let mut system = System::new();
+
+    // need to refresh 2 times to get CPU usage
+    system.refresh_cpu_all();
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    system.refresh_cpu_all();
+
+    let per_core: Vec<f32> = system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect();
+    let cpu_usage = per_core.iter().sum::<f32>() / (per_core.len().max(1) as f32);
+
+    (cpu_usage, per_core)
+}
+
+/// Best-effort guess whether the CPU is currently throttled, given the usage of each core
+/// in percent, in the order of `sample_cpu_usage`.
+///
+/// - Linux: the busy cores (`CPU_THROTTLE_MIN_USAGE`) run below
+///   `CPU_THROTTLE_FREQUENCY_RATIO` of their base frequency, see `read_cpu_throttled`.
+/// - macOS: the XNU thermal level of Intel Macs is above zero. Apple Silicon doesn't
+///   expose it, so this is always `false` there.
+/// - Windows and other platforms: not detected, always `false`.
+pub fn is_cpu_throttled(per_core: &[f32]) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        read_cpu_throttled(std::path::Path::new("/sys/devices/system/cpu"), per_core)
+            .unwrap_or(false)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let _ = per_core;
+        let mut level: libc::c_int = 0;
+        let mut size = std::mem::size_of::<libc::c_int>();
+        let ret = unsafe {
+            libc::sysctlbyname(
+                c"machdep.xcpm.cpu_thermal_level".as_ptr(),
+                &mut level as *mut libc::c_int as *mut libc::c_void,
+                &mut size,
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        ret == 0 && level > 0
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        let _ = per_core;
+        false
+    }
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn read_khz(path: &std::path::Path) -> Option<u64> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+}
+
+/// Base frequency of a core in kHz, from its cpufreq directory. intel_pstate exposes it
+/// as `base_frequency` and amd-pstate as `amd_pstate_nominal_freq`.
+#[cfg(any(target_os = "linux", test))]
+fn read_base_frequency_khz(cpufreq: &std::path::Path) -> Option<u64> {
+    read_khz(&cpufreq.join("base_frequency"))
+        .or_else(|| read_khz(&cpufreq.join("amd_pstate_nominal_freq")))
+}
+
+/// Compares the current and base frequency of the cores listed under `root`, laid out
+/// like /sys/devices/system/cpu, where `cpuN` has the usage `per_core[N]`. Idle cores
+/// clock down on purpose, so only busy cores are compared. `None` when the base
+/// frequency isn't available.
+#[cfg(any(target_os = "linux", test))]
+pub(crate) fn read_cpu_throttled(root: &std::path::Path, per_core: &[f32]) -> Option<bool> {
+    use crate::constants::{CPU_THROTTLE_FREQUENCY_RATIO, CPU_THROTTLE_MIN_USAGE};
+
+    let mut has_base_frequency = false;
+    let (mut current_total, mut base_total) = (0, 0);
+    for entry in std::fs::read_dir(root).ok()?.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let Some(index) = name
+            .strip_prefix("cpu")
+            .filter(|index| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|index| index.parse::<usize>().ok())
+        else {
+            continue;
+        };
+
+        let cpufreq = entry.path().join("cpufreq");
+        let current = read_khz(&cpufreq.join("scaling_cur_freq"));
+        if let (Some(current), Some(base)) = (current, read_base_frequency_khz(&cpufreq)) {
+            has_base_frequency = true;
+            if per_core
+                .get(index)
+                .is_some_and(|usage| *usage >= CPU_THROTTLE_MIN_USAGE)
+            {
+                current_total += current;
+                base_total += base;
+            }
+        }
+    }
+
+    if !has_base_frequency {
+        return None;
+    }
+    Some(
+        base_total > 0
+            && (current_total as f32) < (base_total as f32) * CPU_THROTTLE_FREQUENCY_RATIO,
+    )
+}
+
+/// Parses the nominal frequency that Intel puts at the end of its brand strings,
+/// e.g. "Intel(R) Core(TM) i7-8700 CPU @ 3.20GHz"
+pub fn parse_brand_frequency_mhz(brand: &str) -> Option<u64> {
+    let (_, frequency) = brand.rsplit_once('@')?;
+    let frequency = frequency.trim().to_lowercase();
+    if let Some(ghz) = frequency.strip_suffix("ghz") {
+        let ghz: f64 = ghz.trim().parse().ok()?;
+        Some((ghz * 1000.0).round() as u64)
+    } else {
+        frequency.strip_suffix("mhz")?.trim().parse().ok()
+    }
+}
+
+/// Base (nominal) CPU frequency in MHz, from the first source that knows it:
+///
+/// - Linux: the cpufreq base frequency of the first core
+/// - x86: CPUID leaf 0x16, on Intel CPUs since Skylake
+/// - macOS: the `hw.cpufrequency` sysctl, on Intel Macs
+/// - the frequency at the end of the brand string
+/// - Windows: the rated frequency that sysinfo reads from the power information API
+fn get_base_frequency_mhz(system: &System, brand: &str) -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    if let Some(khz) =
+        read_base_frequency_khz(std::path::Path::new("/sys/devices/system/cpu/cpu0/cpufreq"))
+    {
+        return Some(khz / 1000);
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        #[cfg(target_arch = "x86")]
+        use std::arch::x86::__cpuid;
+        #[cfg(target_arch = "x86_64")]
+        use std::arch::x86_64::__cpuid;
+
+        // SAFETY: CPUID is available on every x86 CPU that can run this binary.
+        // __cpuid is a safe fn since Rust 1.87, but not on the MSRV
+        #[allow(unused_unsafe)]
+        let max_leaf = unsafe { __cpuid(0) }.eax;
+        if max_leaf >= 0x16 {
+            #[allow(unused_unsafe)]
+            let base_mhz = unsafe { __cpuid(0x16) }.eax & 0xffff;
+            if base_mhz > 0 {
+                return Some(base_mhz as u64);
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let mut hz: u64 = 0;
+        let mut size = std::mem::size_of::<u64>();
+        let ret = unsafe {
+            libc::sysctlbyname(
+                c"hw.cpufrequency".as_ptr(),
+                &mut hz as *mut u64 as *mut libc::c_void,
+                &mut size,
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        if ret == 0 && hz > 0 {
+            return Some(hz / 1_000_000);
+        }
+    }
+
+    if let Some(mhz) = parse_brand_frequency_mhz(brand) {
+        return Some(mhz);
+    }
+
+    // elsewhere sysinfo reports the current frequency, which isn't the base frequency
+    if cfg!(target_os = "windows") {
+        return system
+            .cpus()
+            .first()
+            .map(|cpu| cpu.frequency())
+            .filter(|mhz| *mhz > 0);
+    }
+    let _ = system;
+    None
+}
+
+/// Parses a kernel CPU list such as "0-7,16,18-19" into CPU indices
+#[cfg(any(target_os = "linux", test))]
+fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.trim()
+        .split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((start, end)) => Some((start.parse().ok()?..=end.parse().ok()?).collect()),
+            None => range.parse().ok().map(|cpu| vec![cpu]),
+        })
+        .flatten()
+        .collect()
+}
+
+/// Counts the performance and efficiency cores of a hybrid CPU from sysfs, `root` being
+/// laid out like /sys/devices. Returns `None` on CPUs with uniform cores.
+///
+/// Intel hybrid CPUs list their P-core and E-core threads in `cpu_core/cpus` and
+/// `cpu_atom/cpus`. On ARM big.LITTLE, cores with the lowest `cpu_capacity` are the
+/// efficiency cores, the mid and prime cores of three-cluster designs are performance
+/// cores. Threads of the same physical core are counted once, using
+/// `topology/core_cpus_list`.
+#[cfg(any(target_os = "linux", test))]
+pub(crate) fn read_hybrid_topology(root: &std::path::Path) -> Option<(usize, usize)> {
+    let cpu_dir = root.join("system/cpu");
+    let count_cores = |cpus: &[usize]| {
+        let mut cores: Vec<String> = cpus
+            .iter()
+            .map(|cpu| {
+                let siblings = cpu_dir.join(format!("cpu{cpu}/topology/core_cpus_list"));
+                std::fs::read_to_string(siblings)
+                    .map(|list| list.trim().to_string())
+                    .unwrap_or_else(|_| cpu.to_string())
+            })
+            .collect();
+        cores.sort();
+        cores.dedup();
+        cores.len()
+    };
+
+    let read_cpus = |pmu: &str| {
+        std::fs::read_to_string(root.join(pmu).join("cpus"))
+            .ok()
+            .map(|list| parse_cpu_list(&list))
+    };
+    if let (Some(p_cpus), Some(e_cpus)) = (read_cpus("cpu_core"), read_cpus("cpu_atom")) {
+        return Some((count_cores(&p_cpus), count_cores(&e_cpus)));
+    }
+
+    let capacities: Vec<(usize, u64)> = std::fs::read_dir(&cpu_dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let cpu = entry
+                .file_name()
+                .to_str()?
+                .strip_prefix("cpu")?
+                .parse()
+                .ok()?;
+            let capacity = std::fs::read_to_string(entry.path().join("cpu_capacity")).ok()?;
+            Some((cpu, capacity.trim().parse().ok()?))
+        })
+        .collect();
+    let min_capacity = capacities.iter().map(|(_, capacity)| *capacity).min()?;
+    let (e_cpus, p_cpus): (Vec<_>, Vec<_>) = capacities
+        .iter()
+        .partition(|(_, capacity)| *capacity == min_capacity);
+    if p_cpus.is_empty() {
+        return None;
+    }
+    let cpus = |cpus: Vec<&(usize, u64)>| cpus.into_iter().map(|(cpu, _)| *cpu).collect::<Vec<_>>();
+    Some((count_cores(&cpus(p_cpus)), count_cores(&cpus(e_cpus))))
+}
+
+#[cfg(any(target_os = "windows", test))]
+const RELATION_PROCESSOR_CORE: u32 = 0;
+
+/// Counts the performance and efficiency cores in the `RelationProcessorCore` records
+/// returned by `GetLogicalProcessorInformationEx`. Each record is a physical core with
+/// an `EfficiencyClass`, higher meaning faster, and cores of the lowest class are the
+/// efficiency cores. Returns `None` on CPUs with uniform cores.
+#[cfg(any(target_os = "windows", test))]
+pub(crate) fn count_cores_by_efficiency_class(buffer: &[u8]) -> Option<(usize, usize)> {
+    // SYSTEM_LOGICAL_PROCESSOR_INFORMATION_EX starts with Relationship and Size, followed
+    // by PROCESSOR_RELATIONSHIP whose second byte is EfficiencyClass
+    let mut classes = Vec::new();
+    let mut offset = 0;
+    while offset < buffer.len() {
+        let read_u32 =
+            |at: usize| Some(u32::from_ne_bytes(buffer.get(at..at + 4)?.try_into().ok()?));
+        let relationship = read_u32(offset)?;
+        let size = read_u32(offset + 4)? as usize;
+        if size == 0 {
+            return None;
+        }
+        if relationship == RELATION_PROCESSOR_CORE {
+            classes.push(*buffer.get(offset + 9)?);
+        }
+        offset += size;
+    }
+
+    let min_class = *classes.iter().min()?;
+    let e_cores = classes.iter().filter(|class| **class == min_class).count();
+    (e_cores < classes.len()).then_some((classes.len() - e_cores, e_cores))
+}
+
+#[cfg(target_os = "windows")]
+fn read_windows_core_topology() -> Option<(usize, usize)> {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetLogicalProcessorInformationEx(
+            relationship: u32,
+            buffer: *mut u8,
+            returned_length: *mut u32,
+        ) -> i32;
+    }
+
+    // The first call fails with ERROR_INSUFFICIENT_BUFFER and reports the size it needs
+    let mut length = 0u32;
+    unsafe {
+        GetLogicalProcessorInformationEx(RELATION_PROCESSOR_CORE, std::ptr::null_mut(), &mut length)
+    };
+    if length == 0 {
+        return None;
+    }
+    // u64 elements keep the records aligned
+    let mut buffer = vec![0u64; (length as usize).div_ceil(8)];
+    let ret = unsafe {
+        GetLogicalProcessorInformationEx(
+            RELATION_PROCESSOR_CORE,
+            buffer.as_mut_ptr() as *mut u8,
+            &mut length,
+        )
+    };
+    if ret == 0 {
+        log::error!("GetLogicalProcessorInformationEx failed");
+        return None;
+    }
+    let bytes =
+        unsafe { std::slice::from_raw_parts(buffer.as_ptr() as *const u8, length as usize) };
+    count_cores_by_efficiency_class(bytes)
+}
+
+/// Physical performance and efficiency core counts, from sysfs on Linux, the
+/// `hw.perflevel` sysctls on macOS (perflevel0 is the fastest level) and the core
+/// efficiency classes on Windows. CPUs with uniform cores report all cores as
+/// performance cores.
+fn get_core_topology(core_count: usize) -> (usize, usize) {
+    #[cfg(target_os = "linux")]
+    if let Some(topology) = read_hybrid_topology(std::path::Path::new("/sys/devices")) {
+        return topology;
+    }
+
+    #[cfg(target_os = "windows")]
+    if let Some(topology) = read_windows_core_topology() {
+        return topology;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let read_sysctl = |name: &std::ffi::CStr| {
+            let mut value: libc::c_int = 0;
+            let mut size = std::mem::size_of::<libc::c_int>();
+            let ret = unsafe {
+                libc::sysctlbyname(
+                    name.as_ptr(),
+                    &mut value as *mut libc::c_int as *mut libc::c_void,
+                    &mut size,
+                    std::ptr::null_mut(),
+                    0,
+                )
+            };
+            (ret == 0).then_some(value as usize)
+        };
+        if read_sysctl(c"hw.nperflevels").unwrap_or(1) > 1 {
+            if let (Some(p_cores), Some(e_cores)) = (
+                read_sysctl(c"hw.perflevel0.physicalcpu"),
+                read_sysctl(c"hw.perflevel1.physicalcpu"),
+            ) {
+                return (p_cores, e_cores);
+            }
+        }
+    }
+
+    (core_count, 0)
+}
 
 impl CpuStaticInfo {
     pub fn new() -> Self {
@@ -21,9 +432,16 @@ impl CpuStaticInfo {
             .unwrap_or("unknown")
             .to_string();
 
+        let core_count = System::physical_core_count().unwrap_or(0);
+        let (performance_cores, efficiency_cores) = get_core_topology(core_count);
+
         CpuStaticInfo {
+            base_frequency_mhz: get_base_frequency_mhz(&system, &name).unwrap_or(0),
             name,
-            core_count: System::physical_core_count().unwrap_or(0),
+            core_count,
+            logical_cores: system.cpus().len(),
+            performance_cores,
+            efficiency_cores,
             arch: std::env::consts::ARCH.to_string(),
             extensions: CpuStaticInfo::get_extensions(),
         }
@@ -67,6 +485,9 @@ impl CpuStaticInfo {
         if is_x86_feature_detected!("avx2") {
             exts.push("avx2".to_string());
         }
+        if is_x86_feature_detected!("fma") {
+            exts.push("fma".to_string());
+        }
         if is_x86_feature_detected!("avx512f") {
             exts.push("avx512_f".to_string());
         }
@@ -122,8 +543,38 @@ impl CpuStaticInfo {
         exts
     }
 
-    // Cortex always returns empty list for non-x86
-    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    #[cfg(target_arch = "aarch64")]
+    fn get_extensions() -> Vec<String> {
+        use std::arch::is_aarch64_feature_detected;
+
+        let mut exts = vec![];
+
+        if is_aarch64_feature_detected!("neon") {
+            exts.push("neon".to_string());
+        }
+        if is_aarch64_feature_detected!("fp16") {
+            exts.push("fp16".to_string());
+        }
+        if is_aarch64_feature_detected!("dotprod") {
+            exts.push("dotprod".to_string());
+        }
+        if is_aarch64_feature_detected!("i8mm") {
+            exts.push("i8mm".to_string());
+        }
+        if is_aarch64_feature_detected!("bf16") {
+            exts.push("bf16".to_string());
+        }
+        if is_aarch64_feature_detected!("sve") {
+            exts.push("sve".to_string());
+        }
+        if is_aarch64_feature_detected!("sve2") {
+            exts.push("sve2".to_string());
+        }
+
+        exts
+    }
+
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
     fn get_extensions() -> Vec<String> {
         vec![]
     }
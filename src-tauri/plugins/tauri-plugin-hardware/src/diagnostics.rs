@@ -0,0 +1,171 @@
+use crate::{
+    commands::probe_gpus,
+    cpu::{get_cpu_temperatures, sample_cpu_usage},
+    memory::{get_memory_pressure, get_swap_usage},
+    types::{CpuStaticInfo, GpuInfo, HardwareDiagnostics, SubsystemDiagnostic, Vendor},
+    vendor::npu,
+};
+use serde_json::{json, Value};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use sysinfo::System;
+use tauri::Runtime;
+
+/// Runs one probe, turning a panic into an error so the other subsystems still run
+fn run_probe(
+    subsystem: String,
+    backend: Option<String>,
+    probe: impl FnOnce() -> Result<Value, String>,
+) -> SubsystemDiagnostic {
+    let result = catch_unwind(AssertUnwindSafe(probe)).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Err(format!("probe panicked: {}", message))
+    });
+    if let Err(e) = &result {
+        log::warn!("Hardware diagnostics: {} failed: {}", subsystem, e);
+    }
+
+    match result {
+        Ok(values) => SubsystemDiagnostic {
+            subsystem,
+            ok: true,
+            error: None,
+            backend,
+            values,
+        },
+        Err(error) => SubsystemDiagnostic {
+            subsystem,
+            ok: false,
+            error: Some(error),
+            backend,
+            values: Value::Null,
+        },
+    }
+}
+
+fn to_value(value: impl serde::Serialize) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| e.to_string())
+}
+
+/// Library the usage of `gpu` is read from, same dispatch as `GpuInfo::get_usage`
+fn gpu_backend(gpu: &GpuInfo) -> &'static str {
+    match gpu.vendor {
+        Vendor::NVIDIA => "nvml",
+        Vendor::AMD if cfg!(target_os = "linux") => "amdgpu sysfs",
+        Vendor::AMD if cfg!(target_os = "windows") => "adl",
+        Vendor::Apple => "metal",
+        _ if gpu.vulkan_info.is_some() => "vulkan",
+        _ => "none",
+    }
+}
+
+/// Where NPUs are detected from on this platform, same dispatch as `npu::get_npus`
+fn npu_backend() -> &'static str {
+    if cfg!(target_os = "linux") {
+        "accel"
+    } else if cfg!(target_os = "macos") {
+        "apple neural engine"
+    } else if cfg!(target_os = "windows") {
+        "setupapi"
+    } else {
+        "none"
+    }
+}
+
+fn diagnose_cpu() -> SubsystemDiagnostic {
+    run_probe("cpu".to_string(), Some("sysinfo".to_string()), || {
+        let info = CpuStaticInfo::new();
+        if info.logical_cores == 0 {
+            return Err("no CPU cores detected".to_string());
+        }
+        let (usage, per_core) = sample_cpu_usage();
+        Ok(json!({
+            "info": to_value(info)?,
+            "usage": usage,
+            "per_core": per_core,
+            "temperatures": to_value(get_cpu_temperatures())?,
+        }))
+    })
+}
+
+fn diagnose_memory() -> SubsystemDiagnostic {
+    run_probe("memory".to_string(), Some("sysinfo".to_string()), || {
+        let mut system = System::new();
+        system.refresh_memory();
+        if system.total_memory() == 0 {
+            return Err("total memory reported as 0".to_string());
+        }
+        let (swap_used_mb, swap_total_mb) = get_swap_usage(&system);
+        Ok(json!({
+            "total_memory": system.total_memory() / 1024 / 1024, // bytes to MiB
+            "used_memory": system.used_memory() / 1024 / 1024,
+            "swap_used_mb": swap_used_mb,
+            "swap_total_mb": swap_total_mb,
+            "memory_pressure": to_value(get_memory_pressure())?,
+        }))
+    })
+}
+
+fn diagnose_gpus<R: Runtime>(app: &tauri::AppHandle<R>) -> Vec<SubsystemDiagnostic> {
+    let mut gpus = None;
+    let detection = run_probe("gpu".to_string(), None, || {
+        gpus = Some(probe_gpus(app));
+        Ok(Value::Null)
+    });
+    let gpus = match gpus {
+        Some(gpus) if !gpus.is_empty() => gpus,
+        Some(_) => {
+            return vec![SubsystemDiagnostic {
+                error: Some("no GPU detected".to_string()),
+                ok: false,
+                ..detection
+            }]
+        }
+        None => return vec![detection],
+    };
+
+    gpus.into_iter()
+        .map(|gpu| {
+            let subsystem = format!("gpu{}", gpu.index);
+            let backend = gpu_backend(&gpu).to_string();
+            run_probe(subsystem, Some(backend), || {
+                let usage = gpu.get_usage();
+                Ok(json!({
+                    "info": to_value(&gpu)?,
+                    "usage": to_value(usage)?,
+                }))
+            })
+        })
+        .collect()
+}
+
+fn diagnose_npus() -> Vec<SubsystemDiagnostic> {
+    let mut npus = vec![];
+    let detection = run_probe("npu".to_string(), Some(npu_backend().to_string()), || {
+        npus = npu::get_npus_internal().map_err(|e| e.to_string())?;
+        to_value(&npus)
+    });
+    // only reported when present, or when probing them failed
+    if npus.is_empty() && detection.ok {
+        return vec![];
+    }
+    vec![detection]
+}
+
+/// Probes every subsystem independently and collects the results, see
+/// `run_hardware_diagnostics`. Blocks while CPU usage is sampled.
+pub fn run_diagnostics<R: Runtime>(app: &tauri::AppHandle<R>) -> HardwareDiagnostics {
+    let mut subsystems = vec![diagnose_cpu(), diagnose_memory()];
+    subsystems.extend(diagnose_gpus(app));
+    subsystems.extend(diagnose_npus());
+
+    HardwareDiagnostics {
+        plugin_version: env!("CARGO_PKG_VERSION").to_string(),
+        os_name: System::long_os_version().unwrap_or("Unknown".to_string()),
+        kernel_version: System::kernel_version(),
+        subsystems,
+    }
+}
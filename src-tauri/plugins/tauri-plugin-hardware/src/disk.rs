@@ -0,0 +1,87 @@
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::counters::{sample_counter_rates, CounterSample};
+
+/// Cumulative bytes read from and written to all physical disks at `taken_at`
+#[derive(Clone, Copy, Debug)]
+pub struct DiskSample {
+    pub read_bytes: u64,
+    pub written_bytes: u64,
+    pub taken_at: Instant,
+}
+
+impl CounterSample for DiskSample {
+    fn counters(&self) -> (u64, u64) {
+        (self.read_bytes, self.written_bytes)
+    }
+
+    fn taken_at(&self) -> Instant {
+        self.taken_at
+    }
+}
+
+/// Sums the read and written bytes of whole physical disks in `<root>/proc/diskstats`.
+/// Partitions are skipped so they aren't counted twice, and so are virtual block devices
+/// (loop, ram, zram, device-mapper, md), which have no `device` link in `/sys/block`.
+#[cfg(target_os = "linux")]
+pub(crate) fn read_diskstats(root: &std::path::Path) -> Option<(u64, u64)> {
+    // /proc/diskstats always counts in 512-byte sectors, whatever the disk's sector size
+    const SECTOR_SIZE: u64 = 512;
+
+    let content = std::fs::read_to_string(root.join("proc/diskstats")).ok()?;
+    let sys_block = root.join("sys/block");
+    let mut totals = (0u64, 0u64);
+    for line in content.lines() {
+        // major minor name reads merged sectors_read ms writes merged sectors_written ...
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+        let name = fields[2];
+        if !sys_block.join(name).join("device").exists() {
+            continue;
+        }
+        let sectors = |index: usize| fields[index].parse::<u64>().unwrap_or(0);
+        totals.0 = totals.0.saturating_add(sectors(5) * SECTOR_SIZE);
+        totals.1 = totals.1.saturating_add(sectors(9) * SECTOR_SIZE);
+    }
+    Some(totals)
+}
+
+/// Reads the cumulative I/O counters of all physical disks. `/proc/diskstats` on Linux,
+/// IOKit on macOS and the disk performance counters on Windows (both through sysinfo).
+pub fn read_disk_sample() -> DiskSample {
+    #[cfg(target_os = "linux")]
+    let (read_bytes, written_bytes) = read_diskstats(std::path::Path::new("/")).unwrap_or_default();
+
+    #[cfg(not(target_os = "linux"))]
+    let (read_bytes, written_bytes) = {
+        let disks = sysinfo::Disks::new_with_refreshed_list();
+        // several volumes can live on the same disk, count each disk once
+        let mut seen = std::collections::HashSet::new();
+        disks
+            .list()
+            .iter()
+            .filter(|disk| seen.insert(disk.name().to_os_string()))
+            .fold((0u64, 0u64), |(read, written), disk| {
+                let usage = disk.usage();
+                (
+                    read.saturating_add(usage.total_read_bytes),
+                    written.saturating_add(usage.total_written_bytes),
+                )
+            })
+    };
+
+    DiskSample {
+        read_bytes,
+        written_bytes,
+        taken_at: Instant::now(),
+    }
+}
+
+/// Takes a new sample and returns the `(read, write)` rates in bytes per second since
+/// the one stored in `last_sample`, see `counters::sample_counter_rates`.
+pub fn sample_disk_rates(last_sample: &Mutex<Option<DiskSample>>) -> (f64, f64) {
+    sample_counter_rates(last_sample, read_disk_sample)
+}
@@ -1,6 +1,9 @@
 use crate::{
-    constants::{VENDOR_ID_AMD, VENDOR_ID_INTEL, VENDOR_ID_NVIDIA},
+    constants::{
+        VENDOR_ID_AMD, VENDOR_ID_APPLE, VENDOR_ID_INTEL, VENDOR_ID_NVIDIA, VENDOR_ID_QUALCOMM,
+    },
     types::{GpuInfo, GpuUsage, Vendor},
+    vendor::{amd, nvidia},
 };
 
 impl Vendor {
@@ -9,25 +12,54 @@ impl Vendor {
             VENDOR_ID_AMD => Vendor::AMD,
             VENDOR_ID_NVIDIA => Vendor::NVIDIA,
             VENDOR_ID_INTEL => Vendor::Intel,
+            VENDOR_ID_QUALCOMM => Vendor::Qualcomm,
+            VENDOR_ID_APPLE => Vendor::Apple,
             _ => Vendor::Unknown(vendor_id),
         }
     }
 }
 
+impl Vendor {
+    pub fn get_compute_runtime_version(&self) -> Option<String> {
+        match self {
+            Vendor::NVIDIA => nvidia::get_cuda_version(),
+            Vendor::AMD => amd::get_rocm_version(),
+            _ => None,
+        }
+    }
+}
+
 impl GpuInfo {
     pub fn get_usage(&self) -> GpuUsage {
         match self.vendor {
             Vendor::NVIDIA => self.get_usage_nvidia(),
             Vendor::AMD => self.get_usage_amd(),
+            Vendor::Apple => self.get_usage_apple(),
             _ => self.get_usage_unsupported(),
         }
     }
 
+    /// NVML only reports discrete GPUs, otherwise rely on the Vulkan device type
+    pub fn detect_discrete(&self) -> bool {
+        if self.is_integrated {
+            return false;
+        }
+        self.nvidia_info.is_some()
+            || self
+                .vulkan_info
+                .as_ref()
+                .is_some_and(|info| info.device_type == "DISCRETE_GPU")
+    }
+
     pub fn get_usage_unsupported(&self) -> GpuUsage {
         GpuUsage {
             uuid: self.uuid.clone(),
             used_memory: 0,
             total_memory: 0,
+            power_watts: None,
+            core_clock_mhz: None,
+            mem_clock_mhz: None,
+            gpu_utilization: None,
         }
     }
 }
@@ -1,8 +1,15 @@
 mod commands;
 mod constants;
+mod counters;
 pub mod cpu;
+mod diagnostics;
+pub mod disk;
 pub mod gpu;
 mod helpers;
+pub mod memory;
+pub mod network;
+pub mod power;
+mod state;
 mod types;
 pub mod vendor;
 
@@ -10,18 +17,29 @@ pub use constants::*;
 pub use helpers::*;
 pub use types::*;
 
-use std::sync::OnceLock;
-use tauri::Runtime;
+use std::sync::Mutex;
+use tauri::{Manager, Runtime};
 
-static SYSTEM_INFO: OnceLock<SystemInfo> = OnceLock::new();
+static SYSTEM_INFO: Mutex<Option<commands::SystemInfoCache>> = Mutex::new(None);
 
 /// Initialize the hardware plugin
 pub fn init<R: Runtime>() -> tauri::plugin::TauriPlugin<R> {
     tauri::plugin::Builder::new("hardware")
         .invoke_handler(tauri::generate_handler![
             commands::get_system_info,
-            commands::get_system_usage
+            commands::refresh_system_info,
+            commands::get_system_usage,
+            commands::get_cpu_temperature,
+            commands::get_power_status,
+            commands::get_gpu_process_memory,
+            commands::run_hardware_diagnostics,
+            commands::start_usage_stream,
+            commands::stop_usage_stream
         ])
+        .setup(|app, _api| {
+            app.manage(state::HardwareState::default());
+            Ok(())
+        })
         .build()
 }
 
@@ -0,0 +1,199 @@
+use sysinfo::System;
+
+use crate::types::{MemoryBreakdown, MemoryPressure};
+
+/// Returns swap usage in MiB as `(used, total)`, from a `System` whose memory was
+/// refreshed. sysinfo reads SwapTotal/SwapFree from /proc/meminfo on Linux,
+/// vm.swapusage on macOS and the page file on Windows. `None` on other platforms.
+pub fn get_swap_usage(system: &System) -> (Option<u64>, Option<u64>) {
+    if cfg!(any(
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "windows"
+    )) {
+        (
+            Some(system.used_swap() / 1024 / 1024),  // bytes to MiB
+            Some(system.total_swap() / 1024 / 1024), // bytes to MiB
+        )
+    } else {
+        (None, None)
+    }
+}
+
+/// Splits RAM into what apps really use and what the OS can reclaim, so a high
+/// `used_memory` caused by the file cache isn't mistaken for memory pressure.
+///
+/// - Linux: MemAvailable, Cached and Buffers from /proc/meminfo.
+/// - macOS: file-backed (cached files), wired and compressed pages from `host_statistics64`.
+/// - Windows: the system cache from `GetPerformanceInfo`.
+///
+/// Fields a platform can't supply are `None`.
+pub fn get_memory_breakdown(system: &System) -> MemoryBreakdown {
+    #[cfg(target_os = "linux")]
+    {
+        let _ = system;
+        std::fs::read_to_string("/proc/meminfo")
+            .map(|content| parse_meminfo(&content))
+            .unwrap_or_default()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        MemoryBreakdown {
+            available_mb: Some(system.available_memory() / 1024 / 1024), // bytes to MiB
+            ..macos::get_vm_breakdown().unwrap_or_default()
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        MemoryBreakdown {
+            available_mb: Some(system.available_memory() / 1024 / 1024), // bytes to MiB
+            cached_mb: windows_impl::get_system_cache_bytes().map(|bytes| bytes / 1024 / 1024),
+            ..Default::default()
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        let _ = system;
+        MemoryBreakdown::default()
+    }
+}
+
+/// Reads the breakdown from the content of /proc/meminfo, whose values are in KiB
+#[cfg(any(target_os = "linux", test))]
+pub(crate) fn parse_meminfo(content: &str) -> MemoryBreakdown {
+    let field = |name: &str| {
+        content.lines().find_map(|line| {
+            let value = line.strip_prefix(name)?.strip_prefix(':')?;
+            let kib = value
+                .trim()
+                .trim_end_matches("kB")
+                .trim()
+                .parse::<u64>()
+                .ok()?;
+            Some(kib / 1024) // KiB to MiB
+        })
+    };
+    MemoryBreakdown {
+        available_mb: field("MemAvailable"),
+        cached_mb: field("Cached"),
+        buffers_mb: field("Buffers"),
+        wired_mb: None,
+        compressed_mb: None,
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use crate::types::MemoryBreakdown;
+
+    /// Cached files, wired and compressed memory as shown by Activity Monitor
+    #[allow(deprecated)] // mach_host_self, the mach2 crate isn't a dependency
+    pub fn get_vm_breakdown() -> Option<MemoryBreakdown> {
+        let mut stats: libc::vm_statistics64 = unsafe { std::mem::zeroed() };
+        let mut count = libc::HOST_VM_INFO64_COUNT;
+        let ret = unsafe {
+            libc::host_statistics64(
+                libc::mach_host_self(),
+                libc::HOST_VM_INFO64,
+                &mut stats as *mut libc::vm_statistics64 as libc::host_info64_t,
+                &mut count,
+            )
+        };
+        if ret != libc::KERN_SUCCESS {
+            return None;
+        }
+
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as u64;
+        let pages_to_mb = |pages: u64| pages * page_size / 1024 / 1024;
+        Some(MemoryBreakdown {
+            available_mb: None,
+            cached_mb: Some(pages_to_mb(
+                stats.external_page_count as u64 + stats.purgeable_count as u64,
+            )),
+            buffers_mb: None,
+            wired_mb: Some(pages_to_mb(stats.wire_count as u64)),
+            compressed_mb: Some(pages_to_mb(stats.compressor_page_count as u64)),
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows_impl {
+    use libloading::{Library, Symbol};
+
+    // PERFORMANCE_INFORMATION from psapi.h
+    #[repr(C)]
+    #[allow(non_snake_case)]
+    struct PerformanceInformation {
+        cb: u32,
+        CommitTotal: usize,
+        CommitLimit: usize,
+        CommitPeak: usize,
+        PhysicalTotal: usize,
+        PhysicalAvailable: usize,
+        SystemCache: usize,
+        KernelTotal: usize,
+        KernelPaged: usize,
+        KernelNonpaged: usize,
+        PageSize: usize,
+        HandleCount: u32,
+        ProcessCount: u32,
+        ThreadCount: u32,
+    }
+
+    type GetPerformanceInfo = unsafe extern "system" fn(*mut PerformanceInformation, u32) -> i32;
+
+    /// Size of the file cache (standby and modified lists) in bytes
+    pub fn get_system_cache_bytes() -> Option<u64> {
+        unsafe {
+            let lib = Library::new("kernel32.dll").ok()?;
+            let get_performance_info: Symbol<GetPerformanceInfo> =
+                lib.get(b"K32GetPerformanceInfo").ok()?;
+
+            let size = std::mem::size_of::<PerformanceInformation>() as u32;
+            let mut info: PerformanceInformation = std::mem::zeroed();
+            info.cb = size;
+            if get_performance_info(&mut info, size) == 0 {
+                log::warn!("GetPerformanceInfo failed");
+                return None;
+            }
+            Some(info.SystemCache as u64 * info.PageSize as u64)
+        }
+    }
+}
+
+/// Returns the kernel's memory pressure level on macOS, the same signal that
+/// `memory_pressure` and Activity Monitor derive from the vm_stat counters.
+#[cfg(target_os = "macos")]
+pub fn get_memory_pressure() -> Option<MemoryPressure> {
+    let mut level: libc::c_int = 0;
+    let mut size = std::mem::size_of::<libc::c_int>();
+    let ret = unsafe {
+        libc::sysctlbyname(
+            c"kern.memorystatus_vm_pressure_level".as_ptr(),
+            &mut level as *mut libc::c_int as *mut libc::c_void,
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if ret != 0 {
+        return None;
+    }
+
+    // values of DISPATCH_MEMORYPRESSURE_NORMAL, _WARN and _CRITICAL
+    match level {
+        1 => Some(MemoryPressure::Normal),
+        2 => Some(MemoryPressure::Warning),
+        4 => Some(MemoryPressure::Critical),
+        _ => None,
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn get_memory_pressure() -> Option<MemoryPressure> {
+    None
+}
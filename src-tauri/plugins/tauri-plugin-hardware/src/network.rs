@@ -0,0 +1,54 @@
+use std::sync::Mutex;
+use std::time::Instant;
+use sysinfo::Networks;
+
+use crate::counters::{sample_counter_rates, CounterSample};
+
+/// Cumulative bytes received and sent over all non-loopback interfaces at `taken_at`
+#[derive(Clone, Copy, Debug)]
+pub struct NetworkSample {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub taken_at: Instant,
+}
+
+impl CounterSample for NetworkSample {
+    fn counters(&self) -> (u64, u64) {
+        (self.rx_bytes, self.tx_bytes)
+    }
+
+    fn taken_at(&self) -> Instant {
+        self.taken_at
+    }
+}
+
+fn is_loopback(name: &str) -> bool {
+    // "lo" on Linux, "lo0" on macOS, "Loopback Pseudo-Interface 1" on Windows
+    name == "lo" || name.starts_with("lo0") || name.to_lowercase().contains("loopback")
+}
+
+/// Reads the cumulative byte counters of all interfaces except loopback
+pub fn read_network_sample() -> NetworkSample {
+    let networks = Networks::new_with_refreshed_list();
+    let (rx_bytes, tx_bytes) = networks
+        .list()
+        .iter()
+        .filter(|(name, _)| !is_loopback(name))
+        .fold((0u64, 0u64), |(rx, tx), (_, data)| {
+            (
+                rx.saturating_add(data.total_received()),
+                tx.saturating_add(data.total_transmitted()),
+            )
+        });
+    NetworkSample {
+        rx_bytes,
+        tx_bytes,
+        taken_at: Instant::now(),
+    }
+}
+
+/// Takes a new sample and returns the `(rx, tx)` rates in bytes per second since the
+/// one stored in `last_sample`, see `counters::sample_counter_rates`.
+pub fn sample_network_rates(last_sample: &Mutex<Option<NetworkSample>>) -> (f64, f64) {
+    sample_counter_rates(last_sample, read_network_sample)
+}
@@ -0,0 +1,298 @@
+use crate::types::PowerStatus;
+
+#[cfg(any(target_os = "linux", test))]
+use crate::types::{BatteryState, BatteryStatus};
+
+/// Returns whether the machine runs on AC power and the state of its battery.
+/// `battery` is `None` on machines without one, and fields the platform doesn't
+/// report are `None`.
+pub fn get_power_status() -> PowerStatus {
+    #[cfg(target_os = "linux")]
+    {
+        read_power_supplies(std::path::Path::new("/sys/class/power_supply"))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos_impl::get_power_status()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        windows_impl::get_power_status()
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        PowerStatus {
+            ac_connected: None,
+            battery: None,
+        }
+    }
+}
+
+/// Reads the power supplies listed under `root`, laid out like /sys/class/power_supply.
+/// Any other supply that is online counts as AC, e.g. a USB-C charger has type "USB".
+/// Supplies of peripherals (scope "Device") are ignored, and the percentage of machines
+/// with several batteries is their average.
+#[cfg(any(target_os = "linux", test))]
+pub(crate) fn read_power_supplies(root: &std::path::Path) -> PowerStatus {
+    let read = |dir: &std::path::Path, name: &str| {
+        std::fs::read_to_string(dir.join(name))
+            .ok()
+            .map(|value| value.trim().to_string())
+    };
+
+    let mut ac_connected = None;
+    let mut percentages = vec![];
+    let mut states = vec![];
+    for entry in std::fs::read_dir(root).into_iter().flatten().flatten() {
+        let dir = entry.path();
+        if read(&dir, "scope").as_deref() == Some("Device") {
+            continue;
+        }
+        match read(&dir, "type").as_deref() {
+            Some("Battery") => {
+                if let Some(capacity) = read(&dir, "capacity").and_then(|c| c.parse::<f32>().ok()) {
+                    percentages.push(capacity);
+                }
+                states.push(match read(&dir, "status").as_deref() {
+                    Some("Charging") => BatteryState::Charging,
+                    Some("Discharging") => BatteryState::Discharging,
+                    Some("Full") => BatteryState::Full,
+                    Some("Not charging") => BatteryState::NotCharging,
+                    _ => BatteryState::Unknown,
+                });
+            }
+            Some(_) => {
+                if let Some(online) = read(&dir, "online") {
+                    ac_connected = Some(ac_connected.unwrap_or(false) || online == "1");
+                }
+            }
+            None => {}
+        }
+    }
+
+    if states.is_empty() {
+        return PowerStatus {
+            ac_connected,
+            battery: None,
+        };
+    }
+
+    // any battery charging or discharging decides the state of the whole machine
+    let state = [BatteryState::Discharging, BatteryState::Charging]
+        .into_iter()
+        .find(|state| states.contains(state))
+        .unwrap_or(states[0]);
+    PowerStatus {
+        ac_connected,
+        battery: Some(BatteryStatus {
+            percentage: (!percentages.is_empty())
+                .then(|| percentages.iter().sum::<f32>() / percentages.len() as f32),
+            state,
+        }),
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows_impl {
+    use crate::types::{BatteryState, BatteryStatus, PowerStatus};
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct SystemPowerStatus {
+        ac_line_status: u8,
+        battery_flag: u8,
+        battery_life_percent: u8,
+        system_status_flag: u8,
+        battery_life_time: u32,
+        battery_full_life_time: u32,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+    }
+
+    const AC_LINE_UNKNOWN: u8 = 255;
+    const BATTERY_FLAG_CHARGING: u8 = 8;
+    const BATTERY_FLAG_NO_BATTERY: u8 = 128;
+    const BATTERY_FLAG_UNKNOWN: u8 = 255;
+    const BATTERY_PERCENT_UNKNOWN: u8 = 255;
+
+    pub fn get_power_status() -> PowerStatus {
+        let mut status = SystemPowerStatus::default();
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+            log::error!("GetSystemPowerStatus failed");
+            return PowerStatus {
+                ac_connected: None,
+                battery: None,
+            };
+        }
+
+        let ac_connected =
+            (status.ac_line_status != AC_LINE_UNKNOWN).then_some(status.ac_line_status == 1);
+        let battery = match status.battery_flag {
+            BATTERY_FLAG_NO_BATTERY => None,
+            flag => {
+                let percentage = (status.battery_life_percent != BATTERY_PERCENT_UNKNOWN)
+                    .then_some(status.battery_life_percent as f32);
+                let state = if flag == BATTERY_FLAG_UNKNOWN {
+                    BatteryState::Unknown
+                } else if flag & BATTERY_FLAG_CHARGING != 0 {
+                    BatteryState::Charging
+                } else if ac_connected == Some(false) {
+                    BatteryState::Discharging
+                } else if percentage == Some(100.0) {
+                    BatteryState::Full
+                } else {
+                    BatteryState::NotCharging
+                };
+                Some(BatteryStatus { percentage, state })
+            }
+        };
+
+        PowerStatus {
+            ac_connected,
+            battery,
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos_impl {
+    use std::ffi::{c_char, c_void, CStr};
+
+    use crate::types::{BatteryState, BatteryStatus, PowerStatus};
+
+    type CFTypeRef = *const c_void;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPSCopyPowerSourcesInfo() -> CFTypeRef;
+        fn IOPSCopyPowerSourcesList(blob: CFTypeRef) -> CFTypeRef;
+        fn IOPSGetPowerSourceDescription(blob: CFTypeRef, ps: CFTypeRef) -> CFTypeRef;
+        fn IOPSGetProvidingPowerSourceType(blob: CFTypeRef) -> CFTypeRef;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(
+            alloc: CFTypeRef,
+            c_str: *const c_char,
+            encoding: u32,
+        ) -> CFTypeRef;
+        fn CFStringCompare(a: CFTypeRef, b: CFTypeRef, options: usize) -> isize;
+        fn CFArrayGetCount(array: CFTypeRef) -> isize;
+        fn CFArrayGetValueAtIndex(array: CFTypeRef, index: isize) -> CFTypeRef;
+        fn CFDictionaryGetValue(dict: CFTypeRef, key: CFTypeRef) -> CFTypeRef;
+        fn CFNumberGetValue(number: CFTypeRef, the_type: isize, value_ptr: *mut c_void) -> u8;
+        fn CFBooleanGetValue(boolean: CFTypeRef) -> u8;
+        fn CFRelease(cf: CFTypeRef);
+    }
+
+    const K_CF_STRING_ENCODING_UTF8: u32 = 0x08000100;
+    const K_CF_NUMBER_SINT64_TYPE: isize = 4;
+    const K_CF_COMPARE_EQUAL_TO: isize = 0;
+
+    // Owned CFString, released on drop
+    struct CfString(CFTypeRef);
+
+    impl CfString {
+        fn new(s: &CStr) -> Self {
+            CfString(unsafe {
+                CFStringCreateWithCString(std::ptr::null(), s.as_ptr(), K_CF_STRING_ENCODING_UTF8)
+            })
+        }
+
+        fn equals(&self, other: CFTypeRef) -> bool {
+            !other.is_null()
+                && unsafe { CFStringCompare(self.0, other, 0) } == K_CF_COMPARE_EQUAL_TO
+        }
+    }
+
+    impl Drop for CfString {
+        fn drop(&mut self) {
+            unsafe { CFRelease(self.0) }
+        }
+    }
+
+    unsafe fn get_value(dict: CFTypeRef, key: &CStr) -> CFTypeRef {
+        let key = CfString::new(key);
+        CFDictionaryGetValue(dict, key.0)
+    }
+
+    unsafe fn get_i64(dict: CFTypeRef, key: &CStr) -> Option<i64> {
+        let number = get_value(dict, key);
+        let mut value: i64 = 0;
+        let ok = !number.is_null()
+            && CFNumberGetValue(
+                number,
+                K_CF_NUMBER_SINT64_TYPE,
+                &mut value as *mut i64 as *mut c_void,
+            ) != 0;
+        ok.then_some(value)
+    }
+
+    unsafe fn get_bool(dict: CFTypeRef, key: &CStr) -> Option<bool> {
+        let boolean = get_value(dict, key);
+        (!boolean.is_null()).then(|| CFBooleanGetValue(boolean) != 0)
+    }
+
+    pub fn get_power_status() -> PowerStatus {
+        unsafe {
+            let blob = IOPSCopyPowerSourcesInfo();
+            if blob.is_null() {
+                return PowerStatus {
+                    ac_connected: None,
+                    battery: None,
+                };
+            }
+
+            let providing = IOPSGetProvidingPowerSourceType(blob);
+            let ac_connected =
+                (!providing.is_null()).then(|| CfString::new(c"AC Power").equals(providing));
+
+            let mut battery = None;
+            let sources = IOPSCopyPowerSourcesList(blob);
+            if !sources.is_null() {
+                let internal_battery = CfString::new(c"InternalBattery");
+                for i in 0..CFArrayGetCount(sources) {
+                    let description =
+                        IOPSGetPowerSourceDescription(blob, CFArrayGetValueAtIndex(sources, i));
+                    if description.is_null()
+                        || !internal_battery.equals(get_value(description, c"Type"))
+                    {
+                        continue;
+                    }
+
+                    let percentage = match (
+                        get_i64(description, c"Current Capacity"),
+                        get_i64(description, c"Max Capacity"),
+                    ) {
+                        (Some(current), Some(max)) if max > 0 => {
+                            Some(current as f32 * 100.0 / max as f32)
+                        }
+                        _ => None,
+                    };
+                    let state = if get_bool(description, c"Is Charging") == Some(true) {
+                        BatteryState::Charging
+                    } else if get_bool(description, c"Is Charged") == Some(true) {
+                        BatteryState::Full
+                    } else if ac_connected == Some(false) {
+                        BatteryState::Discharging
+                    } else {
+                        BatteryState::NotCharging
+                    };
+                    battery = Some(BatteryStatus { percentage, state });
+                    break;
+                }
+                CFRelease(sources);
+            }
+            CFRelease(blob);
+
+            PowerStatus {
+                ac_connected,
+                battery,
+            }
+        }
+    }
+}
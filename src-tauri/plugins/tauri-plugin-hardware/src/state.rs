@@ -0,0 +1,15 @@
+use std::sync::Mutex;
+use tauri::async_runtime::JoinHandle;
+
+use crate::disk::DiskSample;
+use crate::network::NetworkSample;
+
+#[derive(Default)]
+pub struct HardwareState {
+    /// Background task emitting `hardware://usage` events, if started
+    pub usage_stream: Mutex<Option<JoinHandle<()>>>,
+    /// Network counters from the previous `get_system_usage` call, to compute rates
+    pub network_sample: Mutex<Option<NetworkSample>>,
+    /// Disk counters from the previous `get_system_usage` call, to compute rates
+    pub disk_sample: Mutex<Option<DiskSample>>,
+}
@@ -8,9 +8,396 @@ fn test_system_info() {
     println!("System Static Info: {:?}", info);
 }
 
+#[test]
+fn test_cpu_core_counts() {
+    let info = get_system_info(mock_app().handle().clone());
+    assert!(info.cpu.core_count > 0);
+    assert!(info.cpu.logical_cores >= info.cpu.core_count);
+    assert!(info.cpu.performance_cores > 0);
+}
+
+#[test]
+fn test_gpu_indices() {
+    let info = get_system_info(mock_app().handle().clone());
+    for (index, gpu) in info.gpus.iter().enumerate() {
+        assert_eq!(gpu.index, index);
+        assert!(!(gpu.is_integrated && gpu.is_discrete));
+    }
+}
+
+#[test]
+fn test_read_hybrid_topology() {
+    use crate::cpu::read_hybrid_topology;
+    use std::fs;
+
+    let root = std::env::temp_dir().join(format!("jan-cpu-topology-{}", std::process::id()));
+    let write = |path: &str, value: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, format!("{}\n", value)).unwrap();
+    };
+
+    // uniform CPU
+    write("system/cpu/cpu0/cpu_capacity", "1024");
+    write("system/cpu/cpu1/cpu_capacity", "1024");
+    assert_eq!(read_hybrid_topology(&root), None);
+
+    // big.LITTLE: 2 big cores and 4 little cores
+    for (cpu, capacity) in [(0, 1024), (1, 1024), (2, 446), (3, 446), (4, 446), (5, 446)] {
+        write(
+            &format!("system/cpu/cpu{cpu}/cpu_capacity"),
+            &capacity.to_string(),
+        );
+    }
+    assert_eq!(read_hybrid_topology(&root), Some((2, 4)));
+
+    // three clusters: 1 prime and 3 mid cores are performance cores, 4 little cores
+    for (cpu, capacity) in [(0, 1024), (1, 870), (2, 870), (3, 870)] {
+        write(
+            &format!("system/cpu/cpu{cpu}/cpu_capacity"),
+            &capacity.to_string(),
+        );
+    }
+    for cpu in 4..8 {
+        write(&format!("system/cpu/cpu{cpu}/cpu_capacity"), "325");
+    }
+    assert_eq!(read_hybrid_topology(&root), Some((4, 4)));
+
+    // Intel hybrid: 2 hyper-threaded P-cores (cpu0-3) and 4 E-cores (cpu4-7)
+    write("cpu_core/cpus", "0-3");
+    write("cpu_atom/cpus", "4-7");
+    for (cpu, siblings) in [(0, "0-1"), (1, "0-1"), (2, "2-3"), (3, "2-3")] {
+        write(
+            &format!("system/cpu/cpu{cpu}/topology/core_cpus_list"),
+            siblings,
+        );
+    }
+    for cpu in 4..8 {
+        write(
+            &format!("system/cpu/cpu{cpu}/topology/core_cpus_list"),
+            &cpu.to_string(),
+        );
+    }
+    assert_eq!(read_hybrid_topology(&root), Some((2, 4)));
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_count_cores_by_efficiency_class() {
+    use crate::cpu::count_cores_by_efficiency_class;
+
+    // RelationProcessorCore records with a single GROUP_AFFINITY are 48 bytes
+    let record = |efficiency_class: u8| {
+        let mut record = vec![0u8; 48];
+        record[4..8].copy_from_slice(&48u32.to_ne_bytes());
+        record[9] = efficiency_class;
+        record
+    };
+
+    let hybrid: Vec<u8> = [1, 1, 0, 0, 0, 0].into_iter().flat_map(record).collect();
+    assert_eq!(count_cores_by_efficiency_class(&hybrid), Some((2, 4)));
+
+    let uniform: Vec<u8> = [0, 0, 0, 0].into_iter().flat_map(record).collect();
+    assert_eq!(count_cores_by_efficiency_class(&uniform), None);
+    assert_eq!(count_cores_by_efficiency_class(&[]), None);
+}
+
+#[test]
+fn test_parse_brand_frequency() {
+    use crate::cpu::parse_brand_frequency_mhz;
+
+    assert_eq!(
+        parse_brand_frequency_mhz("Intel(R) Core(TM) i7-8700 CPU @ 3.20GHz"),
+        Some(3200)
+    );
+    assert_eq!(
+        parse_brand_frequency_mhz("Intel(R) Pentium(R) 4 CPU @ 2400MHz"),
+        Some(2400)
+    );
+    assert_eq!(
+        parse_brand_frequency_mhz("AMD Ryzen 9 7950X 16-Core Processor"),
+        None
+    );
+    assert_eq!(parse_brand_frequency_mhz("Apple M2 Pro"), None);
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn test_cpu_extensions() {
+    let info = get_system_info(mock_app().handle().clone());
+    // SSE2 is part of the x86_64 baseline
+    assert!(info.cpu.extensions.contains(&"sse2".to_string()));
+}
+
+#[test]
+fn test_refresh_system_info() {
+    let app = mock_app();
+    let cached = get_system_info(app.handle().clone());
+    let refreshed = refresh_system_info(app.handle().clone());
+    assert_eq!(cached.cpu.name, refreshed.cpu.name);
+    assert_eq!(cached.gpus.len(), refreshed.gpus.len());
+}
+
 #[test]
 fn test_system_usage() {
     let app = mock_app();
-    let usage = get_system_usage(app.handle().clone());
+    let usage = tauri::async_runtime::block_on(get_system_usage(app.handle().clone()));
     println!("System Usage Info: {:?}", usage);
 }
+
+#[test]
+fn test_system_usage_per_core() {
+    let app = mock_app();
+    let first = tauri::async_runtime::block_on(get_system_usage(app.handle().clone()));
+    let second = tauri::async_runtime::block_on(get_system_usage(app.handle().clone()));
+    assert!(!first.per_core.is_empty());
+    assert_eq!(first.per_core.len(), second.per_core.len());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_system_usage_swap() {
+    let app = mock_app();
+    let usage = tauri::async_runtime::block_on(get_system_usage(app.handle().clone()));
+    let (used, total) = (usage.swap_used_mb.unwrap(), usage.swap_total_mb.unwrap());
+    assert!(used <= total);
+    assert!(usage.memory_pressure.is_none());
+}
+
+#[test]
+fn test_hardware_diagnostics() {
+    let app = mock_app();
+    let report =
+        tauri::async_runtime::block_on(run_hardware_diagnostics(app.handle().clone())).unwrap();
+    println!("Hardware Diagnostics: {:?}", report);
+    let subsystems: Vec<&str> = report
+        .subsystems
+        .iter()
+        .map(|diagnostic| diagnostic.subsystem.as_str())
+        .collect();
+    assert_eq!(&subsystems[..2], ["cpu", "memory"]);
+    assert!(report.subsystems[0].ok);
+}
+
+#[test]
+fn test_parse_meminfo() {
+    use crate::memory::parse_meminfo;
+    use crate::types::MemoryBreakdown;
+
+    let content = "MemTotal:       32768000 kB\n\
+                   MemFree:         1024000 kB\n\
+                   MemAvailable:   20480000 kB\n\
+                   Buffers:          512000 kB\n\
+                   Cached:         16384000 kB\n\
+                   SwapCached:        10240 kB\n";
+    assert_eq!(
+        parse_meminfo(content),
+        MemoryBreakdown {
+            available_mb: Some(20000),
+            cached_mb: Some(16000),
+            buffers_mb: Some(500),
+            wired_mb: None,
+            compressed_mb: None,
+        }
+    );
+    assert_eq!(parse_meminfo(""), MemoryBreakdown::default());
+}
+
+#[test]
+fn test_cpu_temperature() {
+    let temperatures = get_cpu_temperature();
+    println!("CPU Temperatures: {:?}", temperatures);
+}
+
+#[test]
+fn test_is_cpu_sensor() {
+    use crate::cpu::is_cpu_sensor;
+
+    assert!(is_cpu_sensor("coretemp Package id 0"));
+    assert!(is_cpu_sensor("k10temp Tctl"));
+    assert!(is_cpu_sensor("PECI CPU"));
+    assert!(!is_cpu_sensor("nvme Composite"));
+    assert!(!is_cpu_sensor("amdgpu edge"));
+}
+
+#[test]
+fn test_read_cpu_throttled() {
+    use crate::cpu::read_cpu_throttled;
+    use std::fs;
+
+    let root = std::env::temp_dir().join(format!("jan-cpu-throttle-{}", std::process::id()));
+    assert_eq!(read_cpu_throttled(&root, &[100.0, 100.0]), None);
+
+    let write = |cpu: &str, current: &str, base: &str| {
+        let dir = root.join(cpu).join("cpufreq");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("scaling_cur_freq"), format!("{}\n", current)).unwrap();
+        fs::write(dir.join("base_frequency"), format!("{}\n", base)).unwrap();
+    };
+    write("cpu0", "1200000", "3000000");
+    write("cpu1", "1400000", "3000000");
+    // cpufreq policy directories are not cores
+    fs::create_dir_all(root.join("cpufreq")).unwrap();
+
+    assert_eq!(read_cpu_throttled(&root, &[95.0, 95.0]), Some(true));
+    // idle cores clocking down is not throttling
+    assert_eq!(read_cpu_throttled(&root, &[5.0, 5.0]), Some(false));
+
+    write("cpu0", "3900000", "3000000");
+    write("cpu1", "3800000", "3000000");
+    assert_eq!(read_cpu_throttled(&root, &[95.0, 95.0]), Some(false));
+
+    // a busy core at full speed next to idle cores clocked down isn't throttled
+    write("cpu2", "800000", "3000000");
+    write("cpu3", "800000", "3000000");
+    assert_eq!(
+        read_cpu_throttled(&root, &[95.0, 2.0, 1.0, 0.0]),
+        Some(false)
+    );
+    // but a busy core clocked down is, whatever the idle cores do
+    assert_eq!(
+        read_cpu_throttled(&root, &[3.0, 0.0, 90.0, 1.0]),
+        Some(true)
+    );
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_power_status() {
+    let status = get_power_status();
+    println!("Power Status: {:?}", status);
+}
+
+#[test]
+fn test_gpu_process_memory() {
+    let usage = get_gpu_process_memory();
+    println!("GPU Process Memory: {:?}", usage);
+}
+
+#[test]
+fn test_read_power_supplies() {
+    use crate::power::read_power_supplies;
+    use crate::types::{BatteryState, BatteryStatus, PowerStatus};
+    use std::fs;
+
+    let root = std::env::temp_dir().join(format!("jan-power-supply-{}", std::process::id()));
+    let write = |device: &str, files: &[(&str, &str)]| {
+        let dir = root.join(device);
+        fs::create_dir_all(&dir).unwrap();
+        for (name, value) in files {
+            fs::write(dir.join(name), format!("{}\n", value)).unwrap();
+        }
+    };
+
+    // desktop: AC adapter only
+    write("AC", &[("type", "Mains"), ("online", "1")]);
+    assert_eq!(
+        read_power_supplies(&root),
+        PowerStatus {
+            ac_connected: Some(true),
+            battery: None,
+        }
+    );
+
+    // laptop on battery, with a wireless mouse battery that must be ignored
+    write("AC", &[("type", "Mains"), ("online", "0")]);
+    write(
+        "BAT0",
+        &[
+            ("type", "Battery"),
+            ("capacity", "42"),
+            ("status", "Discharging"),
+        ],
+    );
+    write(
+        "hid-mouse-battery",
+        &[("type", "Battery"), ("scope", "Device"), ("capacity", "5")],
+    );
+    assert_eq!(
+        read_power_supplies(&root),
+        PowerStatus {
+            ac_connected: Some(false),
+            battery: Some(BatteryStatus {
+                percentage: Some(42.0),
+                state: BatteryState::Discharging,
+            }),
+        }
+    );
+
+    // USB-C power delivery charger, the Mains supply stays offline
+    write(
+        "ucsi-source-psy-USBC000:001",
+        &[("type", "USB"), ("online", "1")],
+    );
+    write(
+        "BAT0",
+        &[
+            ("type", "Battery"),
+            ("capacity", "43"),
+            ("status", "Charging"),
+        ],
+    );
+    assert_eq!(
+        read_power_supplies(&root),
+        PowerStatus {
+            ac_connected: Some(true),
+            battery: Some(BatteryStatus {
+                percentage: Some(43.0),
+                state: BatteryState::Charging,
+            }),
+        }
+    );
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_read_diskstats() {
+    use crate::disk::read_diskstats;
+    use std::fs;
+
+    let root = std::env::temp_dir().join(format!("jan-diskstats-{}", std::process::id()));
+    assert_eq!(read_diskstats(&root), None);
+
+    fs::create_dir_all(root.join("proc")).unwrap();
+    fs::create_dir_all(root.join("sys/block/nvme0n1/device")).unwrap();
+    fs::create_dir_all(root.join("sys/block/loop0")).unwrap();
+    fs::write(
+        root.join("proc/diskstats"),
+        "   7       0 loop0 100 0 800 10 0 0 0 0 0 10 10\n\
+         259       0 nvme0n1 500 20 4000 100 300 10 2000 50 0 120 150\n\
+         259       1 nvme0n1p1 400 20 3000 90 200 10 1000 40 0 100 130\n",
+    )
+    .unwrap();
+    // only the whole physical disk counts, in 512-byte sectors
+    assert_eq!(read_diskstats(&root), Some((4000 * 512, 2000 * 512)));
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_compute_counter_rates() {
+    use crate::counters::compute_counter_rates;
+    use crate::network::NetworkSample;
+    use std::time::{Duration, Instant};
+
+    let start = Instant::now();
+    let previous = NetworkSample {
+        rx_bytes: 1_000,
+        tx_bytes: 500,
+        taken_at: start,
+    };
+    let current = NetworkSample {
+        rx_bytes: 3_000,
+        tx_bytes: 400, // counter went backwards
+        taken_at: start + Duration::from_secs(2),
+    };
+    assert_eq!(compute_counter_rates(None, &current), (0.0, 0.0));
+    assert_eq!(
+        compute_counter_rates(Some(&previous), &current),
+        (1_000.0, 0.0)
+    );
+}
@@ -4,17 +4,29 @@ use crate::vendor::{nvidia::NvidiaInfo, vulkan::VulkanInfo};
 
 #[derive(Clone, Serialize, Debug)]
 pub struct CpuStaticInfo {
-    pub name: String,
-    pub core_count: usize,
+    pub name: String,      // "unknown" if the model name can't be read
+    pub core_count: usize, // physical cores, 0 if unknown
+    pub logical_cores: usize,
+    pub performance_cores: usize, // all physical cores on CPUs without efficiency cores
+    pub efficiency_cores: usize,
+    pub base_frequency_mhz: u64, // 0 if unknown
     pub arch: String,
     pub extensions: Vec<String>,
 }
 
+#[derive(Clone, Serialize, Debug)]
+pub struct CpuTemperature {
+    pub label: String,
+    pub temperature: Option<f32>, // Celsius
+}
+
 #[derive(Debug, Clone)]
 pub enum Vendor {
     AMD,
     NVIDIA,
     Intel,
+    Qualcomm,
+    Apple,
     Unknown(u32),
 }
 
@@ -27,6 +39,8 @@ impl Serialize for Vendor {
             Vendor::AMD => "AMD".serialize(serializer),
             Vendor::NVIDIA => "NVIDIA".serialize(serializer),
             Vendor::Intel => "Intel".serialize(serializer),
+            Vendor::Qualcomm => "Qualcomm".serialize(serializer),
+            Vendor::Apple => "Apple".serialize(serializer),
             Vendor::Unknown(vendor_id) => {
                 let formatted = format!("Unknown (vendor_id: {})", vendor_id);
                 serializer.serialize_str(&formatted)
@@ -37,13 +51,33 @@ impl Serialize for Vendor {
 
 #[derive(Clone, Debug, Serialize)]
 pub struct GpuInfo {
+    /// Position in `SystemInfo::gpus`, which is also the order of `SystemUsage::gpus`
+    pub index: usize,
     pub name: String,
     pub total_memory: u64,
     pub vendor: Vendor,
     pub uuid: String,
-    pub driver_version: String,
+    pub driver_version: Option<String>,
+    /// CUDA version for NVIDIA, ROCm version for AMD
+    pub compute_runtime_version: Option<String>,
     pub nvidia_info: Option<NvidiaInfo>,
     pub vulkan_info: Option<VulkanInfo>,
+    /// Best-effort, `false` when the adapter type is unknown
+    pub is_integrated: bool,
+    /// Derived from the vendor layer by `GpuInfo::detect_discrete`, also `false` when unknown
+    pub is_discrete: bool,
+    /// GPU and CPU share one memory pool (Apple Silicon), `total_memory` is then the
+    /// recommended working set rather than dedicated VRAM
+    pub is_unified: bool,
+    pub unified_memory_total_mb: Option<u64>, // Apple Silicon only
+    pub recommended_working_set_mb: Option<u64>, // Apple Silicon only
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct NpuInfo {
+    pub name: String,
+    pub vendor: Vendor,
+    pub tops: Option<f32>, // estimated INT8 TOPS, if known
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -53,6 +87,7 @@ pub struct SystemInfo {
     pub os_name: String,
     pub total_memory: u64,
     pub gpus: Vec<GpuInfo>,
+    pub npus: Vec<NpuInfo>,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -60,12 +95,93 @@ pub struct GpuUsage {
     pub uuid: String,
     pub used_memory: u64,
     pub total_memory: u64,
+    pub power_watts: Option<f32>,
+    pub core_clock_mhz: Option<u32>,
+    pub mem_clock_mhz: Option<u32>,
+    pub gpu_utilization: Option<f32>, // percent of time the GPU was busy, None if not exposed
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryPressure {
+    Normal,
+    Warning,
+    Critical,
+}
+
+/// Where RAM goes beyond `SystemUsage::used_memory`, in MiB. `None` where the
+/// platform doesn't report a value, see `memory::get_memory_breakdown`.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct MemoryBreakdown {
+    pub available_mb: Option<u64>, // memory that can be given to apps without swapping
+    pub cached_mb: Option<u64>,    // file cache, reclaimable
+    pub buffers_mb: Option<u64>,   // Linux only
+    pub wired_mb: Option<u64>,     // macOS only
+    pub compressed_mb: Option<u64>, // macOS only
 }
 
 #[derive(Serialize, Clone, Debug)]
 pub struct SystemUsage {
     pub cpu: f32,
+    pub per_core: Vec<f32>,
     pub used_memory: u64,
     pub total_memory: u64,
+    pub swap_used_mb: Option<u64>,
+    pub swap_total_mb: Option<u64>,
+    pub memory_pressure: Option<MemoryPressure>, // macOS only
+    pub memory_breakdown: MemoryBreakdown,
+    pub throttling: ThrottlingStatus,
+    pub net_rx_bytes_per_sec: f64, // 0 on the first call
+    pub net_tx_bytes_per_sec: f64,
+    pub disk_read_bytes_per_sec: f64, // 0 on the first call
+    pub disk_write_bytes_per_sec: f64,
     pub gpus: Vec<GpuUsage>,
 }
+
+/// Best-effort throttling flags, see `cpu::is_cpu_throttled` and
+/// `nvidia::is_nvidia_throttled` for how each is detected.
+#[derive(Serialize, Clone, Debug)]
+pub struct ThrottlingStatus {
+    pub cpu_throttled: bool,
+    pub gpu_throttled: Option<bool>, // NVIDIA only
+}
+
+/// Result of probing one subsystem in `run_hardware_diagnostics`
+#[derive(Serialize, Clone, Debug)]
+pub struct SubsystemDiagnostic {
+    pub subsystem: String, // "cpu", "memory", "gpu0", "gpu1", ..., "npu"
+    pub ok: bool,
+    pub error: Option<String>,
+    pub backend: Option<String>, // library or interface the values came from, e.g. "nvml"
+    pub values: serde_json::Value, // raw values gathered, null when the probe failed
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HardwareDiagnostics {
+    pub plugin_version: String,
+    pub os_name: String,
+    pub kernel_version: Option<String>,
+    pub subsystems: Vec<SubsystemDiagnostic>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryState {
+    Charging,
+    Discharging,
+    Full,
+    NotCharging,
+    Unknown,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct BatteryStatus {
+    pub percentage: Option<f32>,
+    pub state: BatteryState,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct PowerStatus {
+    pub ac_connected: Option<bool>,
+    pub battery: Option<BatteryStatus>, // None when the machine has no battery
+}
@@ -1,13 +1,27 @@
-use rmcp::model::{CallToolRequestParam, CallToolResult, Tool};
-use rmcp::{service::RunningService, RoleClient};
+use rmcp::model::{CallToolResult, Content, Tool};
+use rmcp::{
+    service::{Peer, RunningService},
+    RoleClient,
+};
 use serde_json::{Map, Value};
 use std::{collections::HashMap, sync::Arc};
 use tauri::{AppHandle, Emitter, Runtime, State};
 use tokio::{sync::Mutex, time::timeout};
 
 use super::{
-    constants::{DEFAULT_MCP_CONFIG, MCP_TOOL_CALL_TIMEOUT},
-    helpers::{restart_active_mcp_servers, start_mcp_server_with_restart, stop_mcp_servers},
+    constants::{DEFAULT_MCP_CONFIG, MCP_STARTUP_MAX_RESTARTS, MCP_TOOL_CALL_TIMEOUT},
+    helpers::{
+        self, call_mcp_tool_with_timeout, extract_tool_call_timeout, finish_mcp_tool_call,
+        get_cached_tools_or_refresh, get_mcp_server_statuses, hash_mcp_config, query_mcp_events,
+        read_mcp_server_log_tail, register_mcp_tool_call, restart_active_mcp_servers,
+        restart_mcp_servers_and_wait, restart_single_mcp_server, sample_mcp_process_stats,
+        set_mcp_monitoring_paused, start_mcp_server_with_restart, stop_mcp_servers,
+        test_mcp_server_config,
+    },
+    models::{
+        McpError, McpLifecycleEvent, McpProcessStats, McpRestartSummary, McpServerStatus,
+        McpServerTestResult, McpToolCatalog,
+    },
 };
 use crate::core::{app::commands::get_jan_data_folder_path, state::AppState};
 use std::fs;
@@ -23,66 +37,114 @@ pub async fn activate_mcp_server<R: Runtime>(
         state.mcp_servers.clone();
 
     // Use the modified start_mcp_server_with_restart that returns first attempt result
-    start_mcp_server_with_restart(app, servers, name, config, Some(3)).await
+    start_mcp_server_with_restart(app, servers, name, config, Some(MCP_STARTUP_MAX_RESTARTS))
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn deactivate_mcp_server(state: State<'_, AppState>, name: String) -> Result<(), String> {
-    log::info!("Deactivating MCP server: {}", name);
-
-    // First, mark server as manually deactivated to prevent restart
-    // Remove from active servers list to prevent restart
-    {
-        let mut active_servers = state.mcp_active_servers.lock().await;
-        active_servers.remove(&name);
-        log::info!("Removed MCP server {} from active servers list", name);
-    }
-
-    // Mark as not successfully connected to prevent restart logic
-    {
-        let mut connected = state.mcp_successfully_connected.lock().await;
-        connected.insert(name.clone(), false);
-        log::info!("Marked MCP server {} as not successfully connected", name);
-    }
+    helpers::deactivate_mcp_server(&state, &name)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    // Reset restart count
-    {
-        let mut counts = state.mcp_restart_counts.lock().await;
-        counts.remove(&name);
-        log::info!("Reset restart count for MCP server {}", name);
-    }
+/// Stops a single MCP server and keeps it stopped, see `helpers::drain_mcp_server`
+///
+/// # Returns
+/// * `Ok(true)` if a running server was stopped, `Ok(false)` if it wasn't running
+#[tauri::command]
+pub async fn stop_mcp_server(state: State<'_, AppState>, name: String) -> Result<bool, String> {
+    helpers::drain_mcp_server(&state, &name)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    // Now remove and stop the server
+#[tauri::command]
+pub async fn restart_mcp_servers(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
     let servers = state.mcp_servers.clone();
-    let mut servers_map = servers.lock().await;
+    // Stop the servers
+    stop_mcp_servers(state.mcp_servers.clone(), state.mcp_server_pids.clone())
+        .await
+        .map_err(|e| e.to_string())?;
 
-    let service = servers_map
-        .remove(&name)
-        .ok_or_else(|| format!("Server {} not found", name))?;
+    // Restart only previously active servers (like cortex)
+    restart_active_mcp_servers(&app, servers)
+        .await
+        .map_err(|e| e.to_string())?;
 
-    // Release the lock before calling cancel
-    drop(servers_map);
+    app.emit("mcp-update", "MCP servers updated")
+        .map_err(|e| format!("Failed to emit event: {}", e))?;
 
-    service.cancel().await.map_err(|e| e.to_string())?;
-    log::info!("Server {name} stopped successfully and marked as deactivated.");
     Ok(())
 }
 
+/// Restarts all previously active MCP servers and waits until each has started or failed
+///
+/// Unlike `restart_mcp_servers`, this resolves once the whole restart is done, with the
+/// counts also sent in the `mcp_restart_complete` event. `mcp_restart_progress` events
+/// are emitted as each server finishes.
 #[tauri::command]
-pub async fn restart_mcp_servers(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
-    let servers = state.mcp_servers.clone();
-    // Stop the servers
-    stop_mcp_servers(state.mcp_servers.clone()).await?;
+pub async fn restart_all_mcp_servers(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<McpRestartSummary, String> {
+    stop_mcp_servers(state.mcp_servers.clone(), state.mcp_server_pids.clone())
+        .await
+        .map_err(|e| e.to_string())?;
 
-    // Restart only previously active servers (like cortex)
-    restart_active_mcp_servers(&app, servers).await?;
+    let active_servers = state.mcp_active_servers.lock().await.clone();
+    let summary =
+        restart_mcp_servers_and_wait(&app, state.mcp_servers.clone(), active_servers).await;
 
     app.emit("mcp-update", "MCP servers updated")
         .map_err(|e| format!("Failed to emit event: {}", e))?;
 
+    Ok(summary)
+}
+
+/// Stops health checks from removing failing MCP servers and holds restarts until
+/// `resume_mcp_monitoring` is called, e.g. while inspecting a server in a debugger
+///
+/// Running servers are left as they are. Emits `mcp_monitoring_paused`.
+#[tauri::command]
+pub async fn pause_mcp_monitoring(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    set_mcp_monitoring_paused(&app, &state.mcp_monitoring_paused, true);
     Ok(())
 }
 
+/// Resumes MCP health monitoring and restarts paused by `pause_mcp_monitoring`
+#[tauri::command]
+pub async fn resume_mcp_monitoring(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    set_mcp_monitoring_paused(&app, &state.mcp_monitoring_paused, false);
+    Ok(())
+}
+
+/// Restart a single active MCP server with its stored config
+#[tauri::command]
+pub async fn restart_mcp_server(app: AppHandle, name: String) -> Result<(), String> {
+    restart_single_mcp_server(app, name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Checks that a server config launches: spawns the server, completes the handshake and
+/// lists its tools once, then stops it
+///
+/// Nothing is added to the running or active servers, so this can be used to try a
+/// config before saving it. Returns the tool names and server info, or the reason the
+/// server failed to start.
+#[tauri::command]
+pub async fn test_mcp_server(app: AppHandle, config: Value) -> Result<McpServerTestResult, String> {
+    test_mcp_server_config(&app, &config).await
+}
+
 /// Reset MCP restart count for a specific server (like cortex reset)
 #[tauri::command]
 pub async fn reset_mcp_restart_count(
@@ -116,6 +178,63 @@ pub async fn get_connected_servers(
     Ok(servers_map.keys().cloned().collect())
 }
 
+/// Returns the state of every known MCP server: running, connecting or stopped, with
+/// its restart count and the server info from its handshake
+///
+/// This only reads the app state and doesn't contact the servers, unlike the health
+/// checks or `get_tools`, so it is cheap enough to call on every render.
+#[tauri::command]
+pub async fn get_mcp_server_status(
+    state: State<'_, AppState>,
+) -> Result<Vec<McpServerStatus>, String> {
+    Ok(get_mcp_server_statuses(&state).await)
+}
+
+/// Returns the memory and CPU usage of each MCP server process, by server name
+///
+/// The process ids are recorded when the servers are spawned. A server whose process
+/// has exited maps to `None`.
+#[tauri::command]
+pub async fn get_mcp_process_stats(
+    state: State<'_, AppState>,
+) -> Result<HashMap<String, Option<McpProcessStats>>, String> {
+    let server_pids = state.mcp_server_pids.lock().await.clone();
+    // Measuring CPU usage sleeps between samples, keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || sample_mcp_process_stats(&server_pids))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Returns the last `lines` lines of an MCP server's stderr log, oldest first
+///
+/// Returns an empty vec if the server has no log yet.
+#[tauri::command]
+pub async fn get_mcp_server_log<R: Runtime>(
+    app: AppHandle<R>,
+    name: String,
+    lines: usize,
+) -> Result<Vec<String>, String> {
+    let app_path = get_jan_data_folder_path(app);
+    tauri::async_runtime::spawn_blocking(move || {
+        read_mcp_server_log_tail(&app_path, &name, lines).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Returns the latest `limit` MCP lifecycle events, oldest first
+///
+/// Events are recorded when a server starts, connects, fails a health check, restarts,
+/// is given up on or is stopped. Pass `server` to get the timeline of a single server.
+#[tauri::command]
+pub async fn get_mcp_events(
+    state: State<'_, AppState>,
+    server: Option<String>,
+    limit: usize,
+) -> Result<Vec<McpLifecycleEvent>, String> {
+    Ok(query_mcp_events(&state.mcp_events, server.as_deref(), limit).await)
+}
+
 /// Retrieves all available tools from all MCP servers
 ///
 /// # Arguments
@@ -157,32 +276,169 @@ pub async fn get_tools(state: State<'_, AppState>) -> Result<Vec<Tool>, String>
     Ok(all_tools)
 }
 
+/// Returns the tools of all connected MCP servers as one catalog
+///
+/// Each tool is tagged with its server. Servers are queried concurrently, and a server
+/// that fails or times out is reported with an error and its timing instead of failing
+/// the call.
+#[tauri::command]
+pub async fn list_all_mcp_tools(state: State<'_, AppState>) -> Result<McpToolCatalog, String> {
+    Ok(helpers::list_all_mcp_tools(&state.mcp_servers).await)
+}
+
+/// Lists the resources served by a running MCP server
+///
+/// # Arguments
+/// * `state` - Application state containing MCP server connections
+/// * `server` - Name of the server as configured in mcp_config.json
+///
+/// # Returns
+/// * `Result<Value, String>` - `{ "<server>": [resources...] }` if successful, or an error
+///   message if the server isn't running or the request failed
+#[tauri::command]
+pub async fn list_mcp_resources(
+    state: State<'_, AppState>,
+    server: String,
+) -> Result<Value, String> {
+    let servers = state.mcp_servers.lock().await;
+    let service = servers
+        .get(&server)
+        .ok_or_else(|| format!("MCP server {} is not running", server))?;
+
+    let resources = timeout(MCP_TOOL_CALL_TIMEOUT, service.list_all_resources())
+        .await
+        .map_err(|_| format!("Listing resources of MCP server {} timed out", server))?
+        .map_err(|e| e.to_string())?;
+
+    let mut result = Map::new();
+    result.insert(
+        server,
+        serde_json::to_value(resources).map_err(|e| e.to_string())?,
+    );
+    Ok(Value::Object(result))
+}
+
+/// Lists the prompts served by a running MCP server
+///
+/// # Arguments
+/// * `state` - Application state containing MCP server connections
+/// * `server` - Name of the server as configured in mcp_config.json
+///
+/// # Returns
+/// * `Result<Value, String>` - `{ "<server>": [prompts...] }` if successful, or an error
+///   message if the server isn't running or the request failed
+#[tauri::command]
+pub async fn list_mcp_prompts(state: State<'_, AppState>, server: String) -> Result<Value, String> {
+    let servers = state.mcp_servers.lock().await;
+    let service = servers
+        .get(&server)
+        .ok_or_else(|| format!("MCP server {} is not running", server))?;
+
+    let prompts = timeout(MCP_TOOL_CALL_TIMEOUT, service.list_all_prompts())
+        .await
+        .map_err(|_| format!("Listing prompts of MCP server {} timed out", server))?
+        .map_err(|e| e.to_string())?;
+
+    let mut result = Map::new();
+    result.insert(
+        server,
+        serde_json::to_value(prompts).map_err(|e| e.to_string())?,
+    );
+    Ok(Value::Object(result))
+}
+
+/// Returns the tools of a single MCP server, from the cache when available
+///
+/// The cache is filled when the server connects and refreshed on every health check,
+/// so this avoids a round-trip to the server when the tool panel is opened.
+#[tauri::command]
+pub async fn get_cached_mcp_tools(
+    state: State<'_, AppState>,
+    server: String,
+) -> Result<Vec<Tool>, String> {
+    get_cached_tools_or_refresh(&state.mcp_servers, &state.mcp_tool_cache, &server)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Returns the input JSON schema of each tool of an MCP server, by tool name
+///
+/// Uses the same cache as `get_cached_mcp_tools`, so the UI can build tool input
+/// forms without a round-trip to the server.
+#[tauri::command]
+pub async fn get_mcp_tool_schemas(
+    state: State<'_, AppState>,
+    server: String,
+) -> Result<HashMap<String, Value>, String> {
+    helpers::get_mcp_tool_schemas(&state.mcp_servers, &state.mcp_tool_cache, &server)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Returns the input JSON schema of a single tool of an MCP server
+#[tauri::command]
+pub async fn get_mcp_tool_schema(
+    state: State<'_, AppState>,
+    server: String,
+    tool: String,
+) -> Result<Value, String> {
+    let mut schemas =
+        helpers::get_mcp_tool_schemas(&state.mcp_servers, &state.mcp_tool_cache, &server)
+            .await
+            .map_err(|e| e.to_string())?;
+    schemas
+        .remove(&tool)
+        .ok_or_else(|| McpError::ToolNotFound { server, tool }.to_string())
+}
+
 /// Calls a tool on an MCP server by name with optional arguments
 ///
 /// # Arguments
 /// * `state` - Application state containing MCP server connections
 /// * `tool_name` - Name of the tool to call
 /// * `arguments` - Optional map of argument names to values
+/// * `request_id` - Id to cancel the call with `cancel_mcp_tool_call`, generated if not given
+/// * `server_name` - Server to call the tool on, found by tool name if not given. Pass it to
+///   cancel the call with the same server name.
 ///
 /// # Returns
 /// * `Result<CallToolResult, String>` - Result of the tool call if successful, or error message if failed
 ///
 /// This function:
-/// 1. Locks the MCP servers mutex to access server connections
-/// 2. Searches through all servers for one containing the named tool
+/// 1. Clones the peers of all running servers, without holding the servers lock afterwards
+/// 2. Searches through all servers, or only `server_name`, for one containing the named tool
 /// 3. When found, calls the tool on that server with the provided arguments
 /// 4. Returns error if no server has the requested tool
+///
+/// Timeouts and cancellations are returned as a tool error result for the model.
 #[tauri::command]
 pub async fn call_tool(
     state: State<'_, AppState>,
     tool_name: String,
     arguments: Option<Map<String, Value>>,
+    request_id: Option<String>,
+    server_name: Option<String>,
 ) -> Result<CallToolResult, String> {
-    let servers = state.mcp_servers.lock().await;
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let active_servers = state.mcp_active_servers.lock().await.clone();
+    // Clone the peers so a long tool call doesn't block other users of the servers map
+    let peers: Vec<(String, Peer<RoleClient>)> = state
+        .mcp_servers
+        .lock()
+        .await
+        .iter()
+        .filter(|(name, _)| server_name.as_ref().map_or(true, |server| server == *name))
+        .map(|(name, service)| (name.clone(), service.peer().clone()))
+        .collect();
+    if let Some(server) = &server_name {
+        if peers.is_empty() {
+            return Err(McpError::ServerNotFound(server.clone()).to_string());
+        }
+    }
 
     // Iterate through servers and find the first one that contains the tool
-    for (_, service) in servers.iter() {
-        let tools = match service.list_all_tools().await {
+    for (server_name, peer) in peers.iter() {
+        let tools = match peer.list_all_tools().await {
             Ok(tools) => tools,
             Err(_) => continue, // Skip this server if we can't list tools
         };
@@ -193,23 +449,81 @@ pub async fn call_tool(
 
         println!("Found tool {} in server", tool_name);
 
-        // Call the tool with timeout
-        let tool_call = service.call_tool(CallToolRequestParam {
-            name: tool_name.clone().into(),
+        // Call the tool with timeout, per-server override first
+        let tool_call_timeout = active_servers
+            .get(server_name)
+            .map(extract_tool_call_timeout)
+            .unwrap_or(MCP_TOOL_CALL_TIMEOUT);
+
+        let cancel_token = register_mcp_tool_call(&state.mcp_tool_calls, server_name, &request_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let result = call_mcp_tool_with_timeout(
+            peer,
+            server_name,
+            &tool_name,
             arguments,
-        });
-
-        return match timeout(MCP_TOOL_CALL_TIMEOUT, tool_call).await {
-            Ok(result) => result.map_err(|e| e.to_string()),
-            Err(_) => Err(format!(
-                "Tool call '{}' timed out after {} seconds",
-                tool_name,
-                MCP_TOOL_CALL_TIMEOUT.as_secs()
-            )),
+            tool_call_timeout,
+            &cancel_token,
+        )
+        .await;
+        finish_mcp_tool_call(&state.mcp_tool_calls, server_name, &request_id).await;
+
+        return match result {
+            Ok(result) => Ok(result),
+            // Hand timeouts back as a tool error so the model can react to them
+            Err(McpError::ToolCallTimeout {
+                server,
+                tool,
+                timeout_ms,
+            }) => {
+                let payload = serde_json::json!({
+                    "error": "timeout",
+                    "server": server,
+                    "tool": tool,
+                    "timeout_ms": timeout_ms,
+                    "message": format!("Tool call '{tool}' timed out after {timeout_ms}ms and was cancelled"),
+                });
+                Ok(CallToolResult::error(vec![Content::text(
+                    payload.to_string(),
+                )]))
+            }
+            Err(McpError::ToolCallCancelled { server, tool }) => {
+                let payload = serde_json::json!({
+                    "error": "cancelled",
+                    "server": server,
+                    "tool": tool,
+                    "message": format!("Tool call '{tool}' was cancelled by the user"),
+                });
+                Ok(CallToolResult::error(vec![Content::text(
+                    payload.to_string(),
+                )]))
+            }
+            Err(e) => Err(e.to_string()),
         };
     }
 
-    Err(format!("Tool {} not found", tool_name))
+    match server_name {
+        Some(server) => Err(McpError::ToolNotFound {
+            server,
+            tool: tool_name,
+        }
+        .to_string()),
+        None => Err(format!("Tool {} not found", tool_name)),
+    }
+}
+
+/// Cancels a tool call started by `call_tool` with the same `request_id` and `server_name`.
+/// The call returns a cancellation result to the model instead of waiting for the server.
+#[tauri::command]
+pub async fn cancel_mcp_tool_call(
+    state: State<'_, AppState>,
+    server: String,
+    request_id: String,
+) -> Result<(), String> {
+    helpers::cancel_mcp_tool_call(&state.mcp_tool_calls, &server, &request_id)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -229,10 +543,16 @@ pub async fn get_mcp_configs(app: AppHandle) -> Result<String, String> {
 }
 
 #[tauri::command]
-pub async fn save_mcp_configs(app: AppHandle, configs: String) -> Result<(), String> {
+pub async fn save_mcp_configs(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    configs: String,
+) -> Result<(), String> {
     let mut path = get_jan_data_folder_path(app);
     path.push("mcp_config.json");
     log::info!("save mcp configs, path: {:?}", path);
 
+    // Recorded before writing, the watcher may see the change before this returns
+    *state.mcp_saved_config_hash.lock().await = Some(hash_mcp_config(&configs));
     fs::write(path, configs).map_err(|e| e.to_string())
 }
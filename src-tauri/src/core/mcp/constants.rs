@@ -2,9 +2,34 @@ use std::time::Duration;
 
 // MCP Constants
 pub const MCP_TOOL_CALL_TIMEOUT: Duration = Duration::from_secs(30);
+pub const MCP_MAX_TOOL_CALL_TIMEOUT: Duration = Duration::from_secs(10 * 60); // Highest allowed toolCallTimeoutMs
 pub const MCP_BASE_RESTART_DELAY_MS: u64 = 1000; // Start with 1 second
 pub const MCP_MAX_RESTART_DELAY_MS: u64 = 30000; // Cap at 30 seconds
 pub const MCP_BACKOFF_MULTIPLIER: f64 = 2.0; // Double the delay each time
+pub const MCP_CIRCUIT_BREAKER_THRESHOLD: usize = 3; // Verification failures that open the circuit breaker
+pub const MCP_CIRCUIT_BREAKER_WINDOW: Duration = Duration::from_secs(2 * 60); // Rolling window for counting those failures
+pub const MCP_CIRCUIT_BREAKER_COOLDOWN: Duration = Duration::from_secs(5 * 60); // Pause before the half-open probe
+pub const MCP_MAX_CONCURRENT_STARTUPS: usize = 4; // Servers initialized at the same time on launch
+pub const MCP_MAX_CONCURRENT_TOOL_LISTINGS: usize = 4; // Servers queried at the same time by list_all_mcp_tools
+pub const MCP_STARTUP_MAX_RESTARTS: u32 = 3; // Used when starting or activating a server
+pub const MCP_DEFAULT_MAX_RESTARTS: u32 = 5; // Used when the caller doesn't specify a limit
+pub const MCP_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+pub const MCP_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+pub const MCP_MIN_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(1); // Lowest allowed healthCheckIntervalMs
+pub const MCP_MIN_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_millis(100); // Lowest allowed healthCheckTimeoutMs
+pub const MCP_MAX_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(30); // Highest allowed healthCheckTimeoutMs
+pub const MCP_HEALTH_DEGRADED_LATENCY: Duration = Duration::from_secs(1); // Slower responses are reported as degraded
+pub const MCP_HEALTH_HEARTBEAT_CYCLES: u32 = 12; // Re-emit an unchanged status every N health checks
+pub const MCP_RESTART_COUNT_RESET_WINDOW: Duration = Duration::from_secs(10 * 60); // Healthy time before the restart count is forgiven
+pub const MCP_STARTUP_VERIFY_TIMEOUT: Duration = Duration::from_secs(5); // Time a new server has to answer a tool listing
+pub const MCP_STARTUP_VERIFY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+pub const MCP_TEST_SERVER_TIMEOUT: Duration = Duration::from_secs(30); // Spawn, handshake and tool listing of test_mcp_server
+pub const MCP_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5); // Force-kill servers that take longer to stop
+pub const MCP_EVENT_LOG_CAPACITY: usize = 500; // Lifecycle events kept for get_mcp_events
+pub const MCP_MONITORING_PAUSED_POLL_INTERVAL: Duration = Duration::from_secs(1); // How often a paused restart loop checks for resume
+pub const MCP_CONFIG_RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+pub const MCP_OUTPUT_MAX_LINE_CHARS: usize = 2000; // Longer lines of server output are truncated in mcp_server_output events
+pub const MCP_OUTPUT_MAX_LINES_PER_SEC: u32 = 50; // Lines of a server's output emitted per second, the rest only goes to its log file
 
 pub const DEFAULT_MCP_CONFIG: &str = r#"{
   "mcpServers": {
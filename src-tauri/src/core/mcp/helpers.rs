@@ -1,18 +1,59 @@
-use rmcp::{service::RunningService, transport::TokioChildProcess, RoleClient, ServiceExt};
-use serde_json::Value;
-use std::{collections::HashMap, env, sync::Arc, time::Duration};
+use notify::{EventKind, RecursiveMode, Watcher};
+use process_wrap::tokio::{TokioCommandWrap, TokioCommandWrapper};
+use rand::Rng;
+use rmcp::{
+    model::{
+        CallToolRequest, CallToolRequestParam, CallToolResult, CancelledNotification,
+        CancelledNotificationParam, ClientNotification, ClientRequest, ServerInfo, ServerResult,
+        Tool,
+    },
+    service::{Peer, PeerRequestOptions, RunningService, ServiceError},
+    transport::TokioChildProcess,
+    RoleClient, ServiceExt,
+};
+use serde_json::{Map, Value};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    env,
+    future::Future,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System};
 use tauri::{AppHandle, Emitter, Manager, Runtime, State};
 use tokio::{
-    process::Command,
-    sync::Mutex,
+    io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    process::{Child, ChildStderr, Command},
+    sync::{Mutex, Semaphore},
     time::{sleep, timeout},
 };
+use tokio_util::sync::CancellationToken;
 
 use super::constants::{
-    MCP_BACKOFF_MULTIPLIER, MCP_BASE_RESTART_DELAY_MS, MCP_MAX_RESTART_DELAY_MS,
+    MCP_BACKOFF_MULTIPLIER, MCP_BASE_RESTART_DELAY_MS, MCP_CIRCUIT_BREAKER_COOLDOWN,
+    MCP_CIRCUIT_BREAKER_THRESHOLD, MCP_CIRCUIT_BREAKER_WINDOW, MCP_CONFIG_RELOAD_DEBOUNCE,
+    MCP_DEFAULT_MAX_RESTARTS, MCP_EVENT_LOG_CAPACITY, MCP_HEALTH_CHECK_INTERVAL,
+    MCP_HEALTH_CHECK_TIMEOUT, MCP_HEALTH_DEGRADED_LATENCY, MCP_HEALTH_HEARTBEAT_CYCLES,
+    MCP_MAX_CONCURRENT_STARTUPS, MCP_MAX_CONCURRENT_TOOL_LISTINGS, MCP_MAX_HEALTH_CHECK_TIMEOUT,
+    MCP_MAX_RESTART_DELAY_MS, MCP_MAX_TOOL_CALL_TIMEOUT, MCP_MIN_HEALTH_CHECK_INTERVAL,
+    MCP_MIN_HEALTH_CHECK_TIMEOUT, MCP_MONITORING_PAUSED_POLL_INTERVAL,
+    MCP_OUTPUT_MAX_LINES_PER_SEC, MCP_OUTPUT_MAX_LINE_CHARS, MCP_RESTART_COUNT_RESET_WINDOW,
+    MCP_SHUTDOWN_TIMEOUT, MCP_STARTUP_MAX_RESTARTS, MCP_STARTUP_VERIFY_POLL_INTERVAL,
+    MCP_STARTUP_VERIFY_TIMEOUT, MCP_TEST_SERVER_TIMEOUT, MCP_TOOL_CALL_TIMEOUT,
+};
+use super::models::{
+    McpCatalogTool, McpConfigDiff, McpConfigError, McpError, McpHealthStatus, McpLifecycleEvent,
+    McpLifecycleEventKind, McpProcessStats, McpRestartProgress, McpRestartStatus,
+    McpRestartSummary, McpServerHealth, McpServerOutput, McpServerState, McpServerStatus,
+    McpServerTestResult, McpServerToolListing, McpToolCatalog,
 };
 use crate::core::{app::commands::get_jan_data_folder_path, state::AppState};
-use jan_utils::can_override_npx;
+use jan_utils::{can_override_npx, parse_lenient_json};
 
 /// Calculate exponential backoff delay with jitter
 ///
@@ -22,29 +63,27 @@ use jan_utils::can_override_npx;
 /// # Returns
 /// * `u64` - Delay in milliseconds, capped at MCP_MAX_RESTART_DELAY_MS
 pub fn calculate_exponential_backoff_delay(attempt: u32) -> u64 {
+    calculate_exponential_backoff_delay_with_rng(attempt, &mut rand::rng())
+}
+
+/// Same as [`calculate_exponential_backoff_delay`], drawing the jitter from `rng`
+pub fn calculate_exponential_backoff_delay_with_rng<R: Rng + ?Sized>(
+    attempt: u32,
+    rng: &mut R,
+) -> u64 {
     use std::cmp;
 
     // Calculate base exponential delay: base_delay * multiplier^(attempt-1)
-    let exponential_delay =
-        (MCP_BASE_RESTART_DELAY_MS as f64) * MCP_BACKOFF_MULTIPLIER.powi((attempt - 1) as i32);
+    let exponential_delay = (MCP_BASE_RESTART_DELAY_MS as f64)
+        * MCP_BACKOFF_MULTIPLIER.powi(attempt.saturating_sub(1) as i32);
 
     // Cap the delay at maximum
     let capped_delay = cmp::min(exponential_delay as u64, MCP_MAX_RESTART_DELAY_MS);
 
     // Add jitter (±25% randomness) to prevent thundering herd
-    let jitter_range = (capped_delay as f64 * 0.25) as u64;
+    let jitter_range = (capped_delay as f64 * 0.25) as i64;
     let jitter = if jitter_range > 0 {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-
-        // Use attempt number as seed for deterministic but varied jitter
-        let mut hasher = DefaultHasher::new();
-        attempt.hash(&mut hasher);
-        let hash = hasher.finish();
-
-        // Convert hash to jitter value in range [-jitter_range, +jitter_range]
-        let jitter_offset = (hash % (jitter_range * 2)) as i64 - jitter_range as i64;
-        jitter_offset
+        rng.random_range(-jitter_range..=jitter_range)
     } else {
         0
     };
@@ -61,45 +100,305 @@ pub fn calculate_exponential_backoff_delay(attempt: u32) -> u64 {
     final_delay
 }
 
-/// Runs MCP commands by reading configuration from a JSON file and initializing servers
+/// Circuit breaker for the restart loop of a single MCP server
 ///
-/// # Arguments
-/// * `app_path` - Path to the application directory containing mcp_config.json
-/// * `servers_state` - Shared state containing running MCP services
-///
-/// # Returns
-/// * `Ok(())` if servers were initialized successfully
-/// * `Err(String)` if there was an error reading config or starting servers
-pub async fn run_mcp_commands<R: Runtime>(
-    app: &AppHandle<R>,
-    servers_state: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
-) -> Result<(), String> {
+/// Counts servers that fail verification, i.e. quit or don't answer a tool listing soon
+/// after starting. `MCP_CIRCUIT_BREAKER_THRESHOLD` failures within `MCP_CIRCUIT_BREAKER_WINDOW`
+/// open the breaker. Once the cooldown has passed the breaker is half-open: the next
+/// attempt is a probe, which closes it again on success and reopens it on failure.
+#[derive(Debug, Default)]
+pub struct McpCircuitBreaker {
+    failures: VecDeque<Instant>,
+    half_open: bool,
+}
+
+impl McpCircuitBreaker {
+    /// Records a verification failure at `now`, returns `true` if the breaker opens
+    pub fn record_failure(&mut self, now: Instant) -> bool {
+        while self
+            .failures
+            .front()
+            .is_some_and(|failure| now.duration_since(*failure) > MCP_CIRCUIT_BREAKER_WINDOW)
+        {
+            self.failures.pop_front();
+        }
+        self.failures.push_back(now);
+
+        if self.half_open || self.failures.len() >= MCP_CIRCUIT_BREAKER_THRESHOLD {
+            self.failures.clear();
+            self.half_open = true; // the attempt after the cooldown is a probe
+            return true;
+        }
+        false
+    }
+
+    /// Records a successful start, closing the breaker
+    pub fn record_success(&mut self) {
+        self.failures.clear();
+        self.half_open = false;
+    }
+
+    pub fn is_half_open(&self) -> bool {
+        self.half_open
+    }
+}
+
+/// Reads the `mcpServers` map from mcp_config.json in the Jan data folder
+pub fn read_mcp_server_map<R: Runtime>(app: &AppHandle<R>) -> Result<Map<String, Value>, McpError> {
     let app_path = get_jan_data_folder_path(app.clone());
     let app_path_str = app_path.to_str().unwrap().to_string();
     log::trace!(
         "Load MCP configs from {}",
         app_path_str.clone() + "/mcp_config.json"
     );
-    let config_content = std::fs::read_to_string(app_path_str + "/mcp_config.json")
-        .map_err(|e| format!("Failed to read config file: {e}"))?;
+    let config_content =
+        std::fs::read_to_string(app_path_str + "/mcp_config.json").map_err(McpError::ConfigRead)?;
 
-    let mcp_servers: serde_json::Value = serde_json::from_str(&config_content)
-        .map_err(|e| format!("Failed to parse config: {e}"))?;
+    // The file is edited by hand, allow commenting out servers and leaving trailing commas
+    let mcp_servers = parse_lenient_json(&config_content).map_err(McpError::ConfigParse)?;
 
     let server_map = mcp_servers
         .get("mcpServers")
         .and_then(Value::as_object)
-        .ok_or("No mcpServers found in config")?;
+        .ok_or_else(|| McpError::ConfigInvalid("No mcpServers found in config".to_string()))?;
 
     log::trace!("MCP Servers: {server_map:#?}");
+    Ok(server_map.clone())
+}
+
+const MCP_SERVER_CONFIG_FIELDS: &[&str] = &[
+    "command",
+    "args",
+    "env",
+    "cwd",
+    "active",
+    "maxRestarts",
+    "healthCheckIntervalMs",
+    "healthCheckTimeoutMs",
+    "restartCountResetMs",
+    "toolCallTimeoutMs",
+    "startupVerifyTimeoutMs",
+    "init",
+];
+
+/// Number of single-character edits needed to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            curr.push((prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1));
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
 
-    // Collect handles for initial server startup
-    let mut startup_handles = Vec::new();
+/// Checks a server entry from mcp_config.json and returns every problem found
+pub fn validate_mcp_server_config(name: &str, config: &Value) -> Vec<McpConfigError> {
+    let error = |field: &str, message: String| McpConfigError {
+        server: name.to_string(),
+        field: field.to_string(),
+        message,
+    };
 
-    for (name, config) in server_map {
-        if extract_active_status(config) == Some(false) {
-            log::trace!("Server {name} is not active, skipping.");
-            continue;
+    let Some(obj) = config.as_object() else {
+        return vec![error("", "Server config must be an object".to_string())];
+    };
+
+    let mut errors = Vec::new();
+
+    // Fields that are neither known nor a likely typo of a known field are left alone
+    let typo_of = |field: &str| {
+        obj.keys()
+            .find(|key| {
+                !MCP_SERVER_CONFIG_FIELDS.contains(&key.as_str()) && edit_distance(key, field) <= 2
+            })
+            .map(|key| format!(" (found \"{key}\", did you mean \"{field}\"?)"))
+            .unwrap_or_default()
+    };
+
+    match obj.get("command") {
+        None => errors.push(error(
+            "command",
+            format!("Missing required field \"command\"{}", typo_of("command")),
+        )),
+        Some(Value::String(command)) if !command.trim().is_empty() => {}
+        Some(_) => errors.push(error(
+            "command",
+            "\"command\" must be a non-empty string".to_string(),
+        )),
+    }
+
+    match obj.get("args") {
+        None => errors.push(error(
+            "args",
+            format!("Missing required field \"args\"{}", typo_of("args")),
+        )),
+        Some(Value::Array(args)) if args.iter().all(Value::is_string) => {}
+        Some(_) => errors.push(error(
+            "args",
+            "\"args\" must be an array of strings".to_string(),
+        )),
+    }
+
+    match obj.get("env") {
+        None => {}
+        Some(Value::Object(envs)) if envs.values().all(Value::is_string) => {}
+        Some(_) => errors.push(error(
+            "env",
+            "\"env\" must be an object with string values".to_string(),
+        )),
+    }
+
+    match obj.get("cwd") {
+        None => {}
+        Some(Value::String(cwd)) if !cwd.trim().is_empty() => {}
+        Some(_) => errors.push(error(
+            "cwd",
+            "\"cwd\" must be a non-empty string".to_string(),
+        )),
+    }
+
+    match obj.get("init") {
+        None | Some(Value::String(_)) | Some(Value::Object(_)) | Some(Value::Array(_)) => {}
+        Some(_) => errors.push(error(
+            "init",
+            "\"init\" must be a string or a JSON object or array".to_string(),
+        )),
+    }
+
+    if obj.get("active").is_some_and(|active| !active.is_boolean()) {
+        errors.push(error("active", "\"active\" must be a boolean".to_string()));
+    }
+
+    for field in [
+        "maxRestarts",
+        "healthCheckIntervalMs",
+        "healthCheckTimeoutMs",
+        "restartCountResetMs",
+        "toolCallTimeoutMs",
+        "startupVerifyTimeoutMs",
+    ] {
+        if obj.get(field).is_some_and(|value| !value.is_u64()) {
+            errors.push(error(
+                field,
+                format!("\"{field}\" must be a non-negative integer"),
+            ));
+        }
+    }
+
+    for (field, minimum) in [
+        ("healthCheckIntervalMs", MCP_MIN_HEALTH_CHECK_INTERVAL),
+        ("healthCheckTimeoutMs", MCP_MIN_HEALTH_CHECK_TIMEOUT),
+    ] {
+        let minimum_ms = minimum.as_millis() as u64;
+        if obj
+            .get(field)
+            .and_then(Value::as_u64)
+            .is_some_and(|value| value < minimum_ms)
+        {
+            errors.push(error(
+                field,
+                format!("\"{field}\" must be at least {minimum_ms}"),
+            ));
+        }
+    }
+
+    let maximum_ms = MCP_MAX_HEALTH_CHECK_TIMEOUT.as_millis() as u64;
+    if obj
+        .get("healthCheckTimeoutMs")
+        .and_then(Value::as_u64)
+        .is_some_and(|value| value > maximum_ms)
+    {
+        errors.push(error(
+            "healthCheckTimeoutMs",
+            format!("\"healthCheckTimeoutMs\" must be at most {maximum_ms}"),
+        ));
+    }
+
+    errors
+}
+
+/// Removes active servers with an invalid config from the map so the remaining ones
+/// can still start, reporting all problems in a single `mcp_config_invalid` event
+pub fn filter_invalid_mcp_servers<R: Runtime>(
+    app: &AppHandle<R>,
+    server_map: Map<String, Value>,
+) -> Map<String, Value> {
+    let mut errors = Vec::new();
+    let valid_servers = server_map
+        .into_iter()
+        .filter(|(name, config)| {
+            if extract_active_status(config) == Some(false) {
+                return true;
+            }
+            let server_errors = validate_mcp_server_config(name, config);
+            let is_valid = server_errors.is_empty();
+            errors.extend(server_errors);
+            is_valid
+        })
+        .collect();
+
+    if !errors.is_empty() {
+        for error in &errors {
+            log::error!(
+                "Invalid config for MCP server {}: {}",
+                error.server,
+                error.message
+            );
+        }
+        if let Err(e) = app.emit("mcp_config_invalid", &errors) {
+            log::error!("Failed to emit mcp_config_invalid event: {e}");
+        }
+    }
+
+    valid_servers
+}
+
+/// Runs MCP commands by reading configuration from a JSON file and initializing servers
+///
+/// # Arguments
+/// * `app_path` - Path to the application directory containing mcp_config.json
+/// * `servers_state` - Shared state containing running MCP services
+/// * `force` - Restart servers that are already running instead of skipping them
+///
+/// Calling this again, e.g. after a retry, only starts the servers that aren't running,
+/// so no duplicate processes are spawned.
+///
+/// # Returns
+/// * `Ok(())` if servers were initialized successfully
+/// * `Err(McpError)` if there was an error reading the config
+pub async fn run_mcp_commands<R: Runtime>(
+    app: &AppHandle<R>,
+    servers_state: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
+    force: bool,
+) -> Result<(), McpError> {
+    let server_map = filter_invalid_mcp_servers(app, read_mcp_server_map(app)?);
+    let stopped = app
+        .state::<AppState>()
+        .mcp_stopped_servers
+        .lock()
+        .await
+        .clone();
+    let running: HashSet<String> = servers_state
+        .lock()
+        .await
+        .iter()
+        .filter(|(_, service)| !service.is_transport_closed())
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    // Collect initial startup tasks, started with a concurrency limit below
+    let mut startup_tasks = Vec::new();
+
+    for name in select_mcp_servers_to_start(&server_map, &running, &stopped, force) {
+        let config = &server_map[&name];
+        if running.contains(&name) {
+            log::info!("Restarting running MCP server {}", name);
+            stop_running_mcp_instance(&app.state::<AppState>(), &name).await;
         }
 
         let app_clone = app.clone();
@@ -107,15 +406,15 @@ pub async fn run_mcp_commands<R: Runtime>(
         let name_clone = name.clone();
         let config_clone = config.clone();
 
-        // Spawn task for initial startup attempt
-        let handle = tokio::spawn(async move {
+        // Task for initial startup attempt
+        let task = async move {
             // Only wait for the initial startup attempt, not the monitoring
             let result = start_mcp_server_with_restart(
                 app_clone.clone(),
                 servers_clone.clone(),
                 name_clone.clone(),
                 config_clone.clone(),
-                Some(3), // Default max restarts for startup
+                Some(MCP_STARTUP_MAX_RESTARTS),
             )
             .await;
 
@@ -129,17 +428,19 @@ pub async fn run_mcp_commands<R: Runtime>(
             }
 
             (name_clone, result)
-        });
+        };
 
-        startup_handles.push(handle);
+        startup_tasks.push(task);
     }
 
-    // Wait for all initial startup attempts to complete
+    // Wait for all initial startup attempts to complete, starting only a few servers at
+    // a time so a large config doesn't saturate disk and network at launch
     let mut successful_count = 0;
     let mut failed_count = 0;
 
-    for handle in startup_handles {
-        match handle.await {
+    let results = join_with_concurrency_limit(startup_tasks, MCP_MAX_CONCURRENT_STARTUPS).await;
+    for result in results {
+        match result {
             Ok((name, result)) => match result {
                 Ok(_) => {
                     log::info!("MCP server {} initialized successfully", name);
@@ -166,69 +467,326 @@ pub async fn run_mcp_commands<R: Runtime>(
     Ok(())
 }
 
+/// Names of the active servers in `server_map` that `run_mcp_commands` should start:
+/// those not in `running`, or all of them with `force`
+pub fn select_mcp_servers_to_start(
+    server_map: &Map<String, Value>,
+    running: &HashSet<String>,
+    stopped: &HashSet<String>,
+    force: bool,
+) -> Vec<String> {
+    server_map
+        .iter()
+        .filter(|(name, config)| {
+            let active = extract_active_status(config);
+            if active == Some(false) {
+                log::trace!("Server {name} is not active, skipping.");
+                return false;
+            }
+            if active.is_none() && stopped.contains(name.as_str()) {
+                log::info!("MCP server {name} was stopped manually, skipping.");
+                return false;
+            }
+            if !force && running.contains(name.as_str()) {
+                log::info!("MCP server {name} is already running, skipping.");
+                return false;
+            }
+            true
+        })
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// Spawns the tasks with at most `limit` of them running at once, and waits for all of
+/// them. Results are returned in the same order as the tasks.
+pub async fn join_with_concurrency_limit<F, T>(
+    tasks: Vec<F>,
+    limit: usize,
+) -> Vec<Result<T, tokio::task::JoinError>>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(limit.max(1)));
+    let handles: Vec<_> = tasks
+        .into_iter()
+        .map(|task| {
+            let semaphore = semaphore.clone();
+            tokio::spawn(async move {
+                // The semaphore is never closed, so acquiring can't fail
+                let _permit = semaphore.acquire_owned().await.unwrap();
+                task.await
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        results.push(handle.await);
+    }
+    results
+}
+
+/// Milliseconds since the Unix epoch, for event timestamps
+fn unix_timestamp_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Appends an event to the MCP lifecycle event log, dropping the oldest events beyond
+/// `MCP_EVENT_LOG_CAPACITY`. This complements the text logs, it doesn't replace them.
+pub async fn record_mcp_event(
+    events: &Mutex<VecDeque<McpLifecycleEvent>>,
+    server: &str,
+    kind: McpLifecycleEventKind,
+    message: impl Into<String>,
+) {
+    let mut events = events.lock().await;
+    if events.len() >= MCP_EVENT_LOG_CAPACITY {
+        events.pop_front();
+    }
+    events.push_back(McpLifecycleEvent {
+        server: server.to_string(),
+        kind,
+        message: message.into(),
+        timestamp: unix_timestamp_ms(),
+    });
+}
+
+/// Returns the latest `limit` events of the MCP lifecycle event log, oldest first,
+/// optionally only those of `server`
+pub async fn query_mcp_events(
+    events: &Mutex<VecDeque<McpLifecycleEvent>>,
+    server: Option<&str>,
+    limit: usize,
+) -> Vec<McpLifecycleEvent> {
+    let events = events.lock().await;
+    let mut matching: Vec<McpLifecycleEvent> = events
+        .iter()
+        .rev()
+        .filter(|event| server.map_or(true, |server| event.server == server))
+        .take(limit)
+        .cloned()
+        .collect();
+    matching.reverse();
+    matching
+}
+
+/// Emit an `mcp_server_health` event for the frontend status badge
+fn emit_mcp_server_health<R: Runtime>(
+    app: &AppHandle<R>,
+    name: &str,
+    status: McpHealthStatus,
+    latency: Duration,
+) {
+    let payload = McpServerHealth {
+        name: name.to_string(),
+        status,
+        latency_ms: latency.as_millis() as u64,
+        timestamp: unix_timestamp_ms(),
+    };
+    if let Err(e) = app.emit("mcp_server_health", payload) {
+        log::error!("Failed to emit mcp_server_health event: {e}");
+    }
+}
+
 /// Monitor MCP server health without removing it from the HashMap
-pub async fn monitor_mcp_server_handle(
+///
+/// The server has just passed start verification when this is called, so the
+/// first health check only runs after a full `health_check_interval`.
+///
+/// An `mcp_server_health` event is emitted whenever the status changes, and every
+/// `MCP_HEALTH_HEARTBEAT_CYCLES` checks otherwise.
+///
+/// Once the server has passed every health check for `restart_count_reset_window`, its
+/// restart count is reset so occasional crashes don't eventually exhaust `max_restarts`.
+pub async fn monitor_mcp_server_handle<R: Runtime>(
+    app: AppHandle<R>,
     servers_state: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
     name: String,
+    health_check_interval: Duration,
+    health_check_timeout: Duration,
+    restart_count_reset_window: Duration,
 ) -> Option<rmcp::service::QuitReason> {
     log::info!("Monitoring MCP server {} health", name);
 
+    let mut last_status = None;
+    let mut cycles_since_emit = 0;
+    // Any failed health check ends monitoring, so the server has been healthy since this
+    let healthy_since = Instant::now();
+    let mut restart_count_reset = false;
+
     // Monitor server health with periodic checks
     loop {
-        // Small delay between health checks
-        sleep(Duration::from_secs(5)).await;
+        // Delay between health checks
+        sleep(health_check_interval).await;
 
         // Check if server is still healthy by trying to list tools
-        let health_check_result = {
-            let servers = servers_state.lock().await;
-            if let Some(service) = servers.get(&name) {
-                // Try to list tools as a health check with a short timeout
-                match timeout(Duration::from_secs(2), service.list_all_tools()).await {
-                    Ok(Ok(_)) => {
-                        // Server responded successfully
-                        true
-                    }
-                    Ok(Err(e)) => {
-                        log::warn!("MCP server {} health check failed: {}", name, e);
-                        false
-                    }
-                    Err(_) => {
-                        log::warn!("MCP server {} health check timed out", name);
-                        false
-                    }
-                }
-            } else {
-                // Server was removed from HashMap (e.g., by deactivate_mcp_server)
-                log::info!("MCP server {} no longer in running services", name);
-                return Some(rmcp::service::QuitReason::Closed);
+        // Probe a cloned peer, so the servers map isn't locked while waiting on the server
+        let peer = servers_state
+            .lock()
+            .await
+            .get(&name)
+            .map(|service| service.peer().clone());
+        let Some(peer) = peer else {
+            // Server was removed from HashMap (e.g., by deactivate_mcp_server)
+            log::info!("MCP server {} no longer in running services", name);
+            return Some(rmcp::service::QuitReason::Closed);
+        };
+
+        // Try to list tools as a health check with a short timeout
+        let started_at = Instant::now();
+        let result = timeout(health_check_timeout, peer.list_all_tools()).await;
+        let latency = started_at.elapsed();
+        let (status, tools) = match result {
+            Ok(Ok(tools)) if latency > MCP_HEALTH_DEGRADED_LATENCY => {
+                log::warn!(
+                    "MCP server {} health check took {}ms",
+                    name,
+                    latency.as_millis()
+                );
+                (McpHealthStatus::Degraded, Some(tools))
+            }
+            Ok(Ok(tools)) => {
+                // Server responded successfully
+                (McpHealthStatus::Healthy, Some(tools))
+            }
+            Ok(Err(e)) => {
+                log::warn!("MCP server {} health check failed: {}", name, e);
+                (McpHealthStatus::Unreachable, None)
+            }
+            Err(_) => {
+                log::warn!("MCP server {} health check timed out", name);
+                (McpHealthStatus::Unreachable, None)
             }
         };
 
-        if !health_check_result {
+        // The health check already listed the tools, keep the cache up to date with them
+        {
+            let app_state = app.state::<AppState>();
+            let mut tool_cache = app_state.mcp_tool_cache.lock().await;
+            match tools {
+                Some(tools) => tool_cache.insert(name.clone(), tools),
+                None => tool_cache.remove(&name),
+            };
+        }
+
+        cycles_since_emit += 1;
+        if last_status != Some(status) || cycles_since_emit >= MCP_HEALTH_HEARTBEAT_CYCLES {
+            emit_mcp_server_health(&app, &name, status, latency);
+            last_status = Some(status);
+            cycles_since_emit = 0;
+        }
+
+        if status == McpHealthStatus::Unreachable
+            && app
+                .state::<AppState>()
+                .mcp_monitoring_paused
+                .load(Ordering::SeqCst)
+        {
+            log::warn!(
+                "MCP server {} failed health check, keeping it while monitoring is paused",
+                name
+            );
+            continue;
+        }
+
+        if status == McpHealthStatus::Unreachable {
             // Server failed health check - remove it and return
             log::error!(
                 "MCP server {} failed health check, removing from active servers",
                 name
             );
+            record_mcp_event(
+                &app.state::<AppState>().mcp_events,
+                &name,
+                McpLifecycleEventKind::HealthFail,
+                "Health check failed, the server is unreachable",
+            )
+            .await;
             let mut servers = servers_state.lock().await;
             if let Some(service) = servers.remove(&name) {
                 // Try to cancel the service gracefully
-                let _ = service.cancel().await;
+                let server_pids = app.state::<AppState>().mcp_server_pids.clone();
+                let _ = cancel_mcp_service(&server_pids, &name, service).await;
             }
             return Some(rmcp::service::QuitReason::Closed);
         }
+
+        if !restart_count_reset && healthy_since.elapsed() >= restart_count_reset_window {
+            let app_state = app.state::<AppState>();
+            let mut counts = app_state.mcp_restart_counts.lock().await;
+            if let Some(count) = counts.get_mut(&name) {
+                if *count > 0 {
+                    log::info!(
+                        "MCP server {} healthy for {} seconds, resetting restart count from {} to 0.",
+                        name,
+                        restart_count_reset_window.as_secs(),
+                        *count
+                    );
+                    *count = 0;
+                }
+            }
+            restart_count_reset = true;
+        }
+    }
+}
+
+/// Pauses or resumes health-check failures and restarts of all MCP servers, emitting
+/// `mcp_monitoring_paused` with the new state if it changed
+///
+/// While paused, servers failing health checks are kept running and servers that quit
+/// are only restarted once monitoring resumes. Returns whether the state changed.
+pub fn set_mcp_monitoring_paused<R: Runtime>(
+    app: &AppHandle<R>,
+    monitoring_paused: &AtomicBool,
+    paused: bool,
+) -> bool {
+    if monitoring_paused.swap(paused, Ordering::SeqCst) == paused {
+        return false;
+    }
+
+    log::info!(
+        "MCP monitoring {}",
+        if paused { "paused" } else { "resumed" }
+    );
+    if let Err(e) = app.emit(
+        "mcp_monitoring_paused",
+        serde_json::json!({ "paused": paused }),
+    ) {
+        log::error!("Failed to emit mcp_monitoring_paused event: {e}");
+    }
+    true
+}
+
+/// Returns once MCP monitoring isn't paused, checking every
+/// `MCP_MONITORING_PAUSED_POLL_INTERVAL`
+pub async fn wait_while_mcp_monitoring_paused(monitoring_paused: &AtomicBool, name: &str) {
+    if !monitoring_paused.load(Ordering::SeqCst) {
+        return;
+    }
+    log::info!(
+        "MCP monitoring is paused, holding the restart of server {}",
+        name
+    );
+    while monitoring_paused.load(Ordering::SeqCst) {
+        sleep(MCP_MONITORING_PAUSED_POLL_INTERVAL).await;
     }
 }
 
 /// Starts an MCP server with restart monitoring
 /// Returns the result of the first start attempt, then continues with restart monitoring
+///
+/// A `maxRestarts` value in the server config takes precedence over `max_restarts`
 pub async fn start_mcp_server_with_restart<R: Runtime>(
     app: AppHandle<R>,
     servers_state: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
     name: String,
     config: Value,
     max_restarts: Option<u32>,
-) -> Result<(), String> {
+) -> Result<(), McpError> {
     let app_state = app.state::<AppState>();
     let restart_counts = app_state.mcp_restart_counts.clone();
     let active_servers_state = app_state.mcp_active_servers.clone();
@@ -236,8 +794,11 @@ pub async fn start_mcp_server_with_restart<R: Runtime>(
 
     // Store active server config for restart purposes
     store_active_server_config(&active_servers_state, &name, &config).await;
+    app_state.mcp_stopped_servers.lock().await.remove(&name);
 
-    let max_restarts = max_restarts.unwrap_or(5);
+    let max_restarts = extract_max_restarts(&config)
+        .or(max_restarts)
+        .unwrap_or(MCP_DEFAULT_MAX_RESTARTS);
 
     // Try the first start attempt and return its result
     log::info!("Starting MCP server {} (Initial attempt)", name);
@@ -277,10 +838,7 @@ pub async fn start_mcp_server_with_restart<R: Runtime>(
             } else {
                 // Server failed verification, don't monitor for restarts
                 log::error!("MCP server {} failed verification after startup", name);
-                Err(format!(
-                    "MCP server {} failed verification after startup",
-                    name
-                ))
+                Err(McpError::VerificationFailed(name))
             }
         }
         Err(e) => {
@@ -304,7 +862,13 @@ pub async fn start_restart_loop<R: Runtime>(
     restart_counts: Arc<Mutex<HashMap<String, u32>>>,
     successfully_connected: Arc<Mutex<HashMap<String, bool>>>,
 ) {
+    let mut circuit_breaker = McpCircuitBreaker::default();
+    let events = app.state::<AppState>().mcp_events.clone();
+    let monitoring_paused = app.state::<AppState>().mcp_monitoring_paused.clone();
+
     loop {
+        wait_while_mcp_monitoring_paused(&monitoring_paused, &name).await;
+
         let current_restart_count = {
             let mut counts = restart_counts.lock().await;
             let count = counts.entry(name.clone()).or_insert(0);
@@ -327,6 +891,13 @@ pub async fn start_restart_loop<R: Runtime>(
             ) {
                 log::error!("Failed to emit mcp_max_restarts_reached event: {e}");
             }
+            record_mcp_event(
+                &events,
+                &name,
+                McpLifecycleEventKind::GiveUp,
+                format!("Reached maximum restart attempts ({max_restarts})"),
+            )
+            .await;
             break;
         }
 
@@ -336,16 +907,31 @@ pub async fn start_restart_loop<R: Runtime>(
             current_restart_count,
             max_restarts
         );
+        record_mcp_event(
+            &events,
+            &name,
+            McpLifecycleEventKind::Restart,
+            format!("Restart attempt {current_restart_count}/{max_restarts}"),
+        )
+        .await;
 
-        // Calculate exponential backoff delay
-        let delay_ms = calculate_exponential_backoff_delay(current_restart_count);
-        log::info!(
-            "Waiting {}ms before restart attempt {} for MCP server {}",
-            delay_ms,
-            current_restart_count,
-            name
-        );
-        sleep(Duration::from_millis(delay_ms)).await;
+        // The probe after a cooldown starts right away, the cooldown already waited
+        if circuit_breaker.is_half_open() {
+            log::info!(
+                "Circuit breaker for MCP server {} is half-open, probing",
+                name
+            );
+        } else {
+            // Calculate exponential backoff delay
+            let delay_ms = calculate_exponential_backoff_delay(current_restart_count);
+            log::info!(
+                "Waiting {}ms before restart attempt {} for MCP server {}",
+                delay_ms,
+                current_restart_count,
+                name
+            );
+            sleep(Duration::from_millis(delay_ms)).await;
+        }
 
         // Attempt to restart the server
         let start_result = schedule_mcp_start_task(
@@ -371,8 +957,16 @@ pub async fn start_restart_loop<R: Runtime>(
                         "MCP server {} failed verification after restart - stopping permanently",
                         name
                     );
+                    record_mcp_event(
+                        &events,
+                        &name,
+                        McpLifecycleEventKind::GiveUp,
+                        "Failed verification after restart",
+                    )
+                    .await;
                     break;
                 }
+                circuit_breaker.record_success();
 
                 // Reset restart count on successful restart with verification
                 {
@@ -390,8 +984,17 @@ pub async fn start_restart_loop<R: Runtime>(
                 }
 
                 // Monitor the server again
-                let quit_reason =
-                    monitor_mcp_server_handle(servers_state.clone(), name.clone()).await;
+                let (health_check_interval, health_check_timeout) =
+                    extract_health_check_settings(&config);
+                let quit_reason = monitor_mcp_server_handle(
+                    app.clone(),
+                    servers_state.clone(),
+                    name.clone(),
+                    health_check_interval,
+                    health_check_timeout,
+                    extract_restart_count_reset_window(&config),
+                )
+                .await;
 
                 log::info!("MCP server {} quit with reason: {:?}", name, quit_reason);
 
@@ -407,6 +1010,13 @@ pub async fn start_restart_loop<R: Runtime>(
                         "MCP server {} failed before establishing successful connection - stopping permanently",
                         name
                     );
+                    record_mcp_event(
+                        &events,
+                        &name,
+                        McpLifecycleEventKind::GiveUp,
+                        "Failed before establishing a successful connection",
+                    )
+                    .await;
                     break;
                 }
 
@@ -429,6 +1039,12 @@ pub async fn start_restart_loop<R: Runtime>(
             }
             Err(e) => {
                 log::error!("Failed to restart MCP server {}: {}", name, e);
+                let failed_verification = matches!(
+                    e,
+                    McpError::QuitImmediately(_)
+                        | McpError::VerificationFailed(_)
+                        | McpError::VerificationTimeout { .. }
+                );
 
                 // Check if server was marked as successfully connected before
                 let was_connected = {
@@ -442,20 +1058,62 @@ pub async fn start_restart_loop<R: Runtime>(
                         "MCP server {} failed restart and was never successfully connected - stopping permanently",
                         name
                     );
+                    record_mcp_event(
+                        &events,
+                        &name,
+                        McpLifecycleEventKind::GiveUp,
+                        format!("Failed to restart and never connected: {e}"),
+                    )
+                    .await;
                     break;
                 }
+
+                // No point in a cooldown when the next attempt would give up anyway
+                if failed_verification
+                    && circuit_breaker.record_failure(Instant::now())
+                    && current_restart_count < max_restarts
+                {
+                    log::warn!(
+                        "MCP server {} keeps quitting right after starting, pausing restarts for {}s",
+                        name,
+                        MCP_CIRCUIT_BREAKER_COOLDOWN.as_secs()
+                    );
+                    if let Err(e) = app.emit(
+                        "mcp_circuit_open",
+                        serde_json::json!({
+                            "server": name,
+                            "cooldown_ms": MCP_CIRCUIT_BREAKER_COOLDOWN.as_millis() as u64,
+                        }),
+                    ) {
+                        log::error!("Failed to emit mcp_circuit_open event: {e}");
+                    }
+                    record_mcp_event(
+                        &events,
+                        &name,
+                        McpLifecycleEventKind::CircuitOpen,
+                        format!(
+                            "Quit right after starting repeatedly, pausing restarts for {}s",
+                            MCP_CIRCUIT_BREAKER_COOLDOWN.as_secs()
+                        ),
+                    )
+                    .await;
+                    sleep(MCP_CIRCUIT_BREAKER_COOLDOWN).await;
+                }
                 // Continue the loop for another restart attempt
             }
         }
     }
 }
 
-pub async fn schedule_mcp_start_task<R: Runtime>(
-    app: tauri::AppHandle<R>,
-    servers: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
-    name: String,
-    config: Value,
-) -> Result<(), String> {
+/// Builds the command that launches an MCP server from its config
+///
+/// `npx` and `uvx` are swapped for the bundled bun and uv. The args, env and `cwd` of
+/// the config are applied, with `${VAR}` references expanded.
+fn build_mcp_command<R: Runtime>(
+    app: &AppHandle<R>,
+    name: &str,
+    config: &Value,
+) -> Result<Command, McpError> {
     let app_path = get_jan_data_folder_path(app.clone());
     let exe_path = env::current_exe().expect("Failed to get current exe path");
     let exe_parent_path = exe_path
@@ -463,8 +1121,11 @@ pub async fn schedule_mcp_start_task<R: Runtime>(
         .expect("Executable must have a parent directory");
     let bin_path = exe_parent_path.to_path_buf();
 
-    let (command, args, envs) = extract_command_args(&config)
-        .ok_or_else(|| format!("Failed to extract command args from config for {name}"))?;
+    let (command, args, envs) = extract_command_args(config).ok_or_else(|| {
+        McpError::ConfigInvalid(format!(
+            "Failed to extract command args from config for {name}"
+        ))
+    })?;
 
     let mut cmd = Command::new(command.clone());
 
@@ -492,74 +1153,156 @@ pub async fn schedule_mcp_start_task<R: Runtime>(
         cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW: prevents shell window on Windows
     }
 
-    let app_path_str = app_path.to_str().unwrap().to_string();
-    let log_file_path = format!("{}/logs/app.log", app_path_str);
-    match std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(log_file_path)
+    // A group of its own lets a force kill reach the processes the server spawned too,
+    // wrappers like npx, uvx or a shell script don't pass SIGKILL on
+    #[cfg(unix)]
     {
-        Ok(file) => {
-            cmd.stderr(std::process::Stdio::from(file));
-        }
-        Err(err) => {
-            log::error!("Failed to open log file: {}", err);
-        }
-    };
+        cmd.process_group(0);
+    }
 
     cmd.kill_on_drop(true);
-    log::trace!("Command: {cmd:#?}");
 
     args.iter().filter_map(Value::as_str).for_each(|arg| {
         cmd.arg(arg);
     });
-    envs.iter().for_each(|(k, v)| {
+    for (k, v) in envs.iter() {
         if let Some(v_str) = v.as_str() {
-            cmd.env(k, v_str);
+            let value = expand_env_vars(v_str).map_err(|var| {
+                let error = McpError::MissingEnvVar {
+                    server: name.to_string(),
+                    key: k.clone(),
+                    var,
+                };
+                log::error!("{error}");
+                error
+            })?;
+            cmd.env(k, value);
         }
-    });
+    }
+
+    // Servers inherit Jan's working directory unless the config sets one
+    if let Some(cwd) = config.get("cwd").and_then(Value::as_str) {
+        let working_dir = resolve_working_dir(cwd).map_err(|var| McpError::MissingEnvVar {
+            server: name.to_string(),
+            key: "cwd".to_string(),
+            var,
+        })?;
+        if !working_dir.is_dir() {
+            let error = McpError::WorkingDirNotFound {
+                server: name.to_string(),
+                path: working_dir.display().to_string(),
+            };
+            log::error!("{error}");
+            return Err(error);
+        }
+        cmd.current_dir(working_dir);
+    }
+
+    log::trace!("Command: {cmd:#?}");
+    Ok(cmd)
+}
+
+pub async fn schedule_mcp_start_task<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    servers: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
+    name: String,
+    config: Value,
+) -> Result<(), McpError> {
+    record_mcp_event(
+        &app.state::<AppState>().mcp_events,
+        &name,
+        McpLifecycleEventKind::Start,
+        "Starting server",
+    )
+    .await;
+
+    let mut cmd = build_mcp_command(&app, &name, &config)?;
 
+    let app_path = get_jan_data_folder_path(app.clone());
+    // Stderr must not be inherited, it would be lost on Windows (`CREATE_NO_WINDOW`) and
+    // would interleave with Jan's own stderr elsewhere
+    cmd.stderr(std::process::Stdio::piped());
+
+    let stderr = CaptureStderr::default();
+    let mut cmd = TokioCommandWrap::from(cmd);
+    cmd.wrap(stderr.clone());
     let process = TokioChildProcess::new(cmd).map_err(|e| {
         log::error!("Failed to run command {name}: {e}");
-        format!("Failed to run command {name}: {e}")
+        McpError::SpawnFailed {
+            server: name.clone(),
+            source: e,
+        }
     })?;
 
-    let service = ()
-        .serve(process)
-        .await
-        .map_err(|e| format!("Failed to start MCP server {name}: {e}"))?;
-
-    // Get peer info and clone the needed values before moving the service
-    let (server_name, server_version) = {
-        let server_info = service.peer_info();
-        log::trace!("Connected to server: {server_info:#?}");
-        (
-            server_info.unwrap().server_info.name.clone(),
-            server_info.unwrap().server_info.version.clone(),
-        )
-    };
+    // Remember the process id so the server can be force-killed if it hangs on shutdown
+    let pid = process.id();
+    if let Some(pid) = pid {
+        let app_state = app.state::<AppState>();
+        app_state
+            .mcp_server_pids
+            .lock()
+            .await
+            .insert(name.clone(), pid);
+    }
 
-    // Now move the service into the HashMap
-    servers.lock().await.insert(name.clone(), service);
-    log::info!("Server {name} started successfully.");
+    if let Some(stderr) = stderr.take() {
+        let log_file = open_mcp_server_log_file(&app_path, &name)
+            .map_err(|e| log::error!("Failed to open log file for MCP server {name}: {e}"))
+            .ok();
+        forward_mcp_server_output(app.clone(), name.clone(), stderr, log_file);
+    }
 
-    // Wait a short time to verify the server is stable before marking as connected
-    // This prevents race conditions where the server quits immediately
-    let verification_delay = Duration::from_millis(500);
-    sleep(verification_delay).await;
+    let connected = async {
+        // Servers that expect a bootstrap message get it before the handshake
+        let (process, mut stdin) = process.split();
+        if let Some(payload) = extract_init_payload(&config) {
+            write_mcp_init_payload(
+                &mut stdin,
+                &name,
+                &payload,
+                extract_startup_verify_timeout(&config),
+            )
+            .await?;
+        }
 
-    // Check if server is still running after the verification delay
-    let server_still_running = {
-        let servers_map = servers.lock().await;
-        servers_map.contains_key(&name)
-    };
+        let service =
+            ().serve((process, stdin))
+                .await
+                .map_err(|e| McpError::StartFailed {
+                    server: name.clone(),
+                    reason: e.to_string(),
+                })?;
+
+        // Get peer info and clone the needed values before moving the service
+        let (server_name, server_version) = {
+            let server_info = service.peer_info();
+            log::trace!("Connected to server: {server_info:#?}");
+            (
+                server_info.unwrap().server_info.name.clone(),
+                server_info.unwrap().server_info.version.clone(),
+            )
+        };
 
-    if !server_still_running {
-        return Err(format!(
-            "MCP server {} quit immediately after starting",
-            name
-        ));
+        // Now move the service into the HashMap
+        servers.lock().await.insert(name.clone(), service);
+        log::info!("Server {name} started successfully.");
+
+        // Wait for the server to answer before marking it as connected, so a server that
+        // quits right after the handshake isn't reported as started
+        let tools =
+            verify_mcp_server_started(&servers, &name, extract_startup_verify_timeout(&config))
+                .await?;
+        Ok((server_name, server_version, tools))
     }
+    .await;
+
+    let (server_name, server_version, tools) = match connected {
+        Ok(connected) => connected,
+        Err(e) => {
+            discard_failed_mcp_server(&app, &servers, &name, pid).await;
+            return Err(e);
+        }
+    };
 
     // Mark server as successfully connected (for restart policy)
     {
@@ -568,6 +1311,20 @@ pub async fn schedule_mcp_start_task<R: Runtime>(
         connected.insert(name.clone(), true);
         log::info!("Marked MCP server {} as successfully connected", name);
     }
+    record_mcp_event(
+        &app.state::<AppState>().mcp_events,
+        &name,
+        McpLifecycleEventKind::Connected,
+        format!("Connected to {server_name} {server_version}"),
+    )
+    .await;
+
+    // Replace any tools cached from a previous connection
+    app.state::<AppState>()
+        .mcp_tool_cache
+        .lock()
+        .await
+        .insert(name.clone(), tools);
 
     // Emit event to the frontend
     let event = format!("mcp-connected");
@@ -575,12 +1332,335 @@ pub async fn schedule_mcp_start_task<R: Runtime>(
         "name": server_name,
         "version": server_version,
     });
-    app.emit(&event, payload)
-        .map_err(|e| format!("Failed to emit event: {}", e))?;
+    app.emit(&event, payload).map_err(McpError::Emit)?;
 
     Ok(())
 }
 
+/// Stops a server whose startup failed after it was spawned and forgets its process id,
+/// so a later force kill can't hit an unrelated process that reused the id
+async fn discard_failed_mcp_server<R: Runtime>(
+    app: &AppHandle<R>,
+    servers: &Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
+    name: &str,
+    pid: Option<u32>,
+) {
+    let server_pids = app.state::<AppState>().mcp_server_pids.clone();
+    let service = servers.lock().await.remove(name);
+    if let Some(service) = service {
+        if let Err(e) = cancel_mcp_service(&server_pids, name, service).await {
+            log::warn!("Failed to stop MCP server {name} after a failed start: {e}");
+        }
+    }
+
+    // A restart may have recorded a newer process under the same name meanwhile
+    let mut server_pids = server_pids.lock().await;
+    if pid.is_some() && server_pids.get(name) == pid.as_ref() {
+        server_pids.remove(name);
+    }
+}
+
+/// Launches a server from `config` once to check that it works: spawns it, completes
+/// the handshake and lists its tools, then shuts it down
+///
+/// The server is never added to the running servers, the active servers or the restart
+/// machinery. Errors are returned verbatim, followed by what the server wrote to stderr.
+pub async fn test_mcp_server_config<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &Value,
+) -> Result<McpServerTestResult, String> {
+    let errors = validate_mcp_server_config(MCP_TEST_SERVER_NAME, config);
+    if !errors.is_empty() {
+        let messages: Vec<String> = errors.into_iter().map(|error| error.message).collect();
+        return Err(messages.join("\n"));
+    }
+
+    let stderr_path = env::temp_dir().join(format!("jan-mcp-test-{}.log", uuid::Uuid::new_v4()));
+    let result = timeout(
+        MCP_TEST_SERVER_TIMEOUT,
+        run_mcp_server_test(app, config, &stderr_path),
+    )
+    .await
+    .unwrap_or_else(|_| {
+        Err(format!(
+            "Server didn't respond within {} seconds",
+            MCP_TEST_SERVER_TIMEOUT.as_secs()
+        ))
+    });
+
+    let stderr = std::fs::read_to_string(&stderr_path).unwrap_or_default();
+    let _ = std::fs::remove_file(&stderr_path);
+    result.map_err(|e| match stderr.trim() {
+        "" => e,
+        stderr => format!("{e}\n\nServer output:\n{stderr}"),
+    })
+}
+
+const MCP_TEST_SERVER_NAME: &str = "test";
+
+async fn run_mcp_server_test<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &Value,
+    stderr_path: &std::path::Path,
+) -> Result<McpServerTestResult, String> {
+    let mut cmd =
+        build_mcp_command(app, MCP_TEST_SERVER_NAME, config).map_err(|e| e.to_string())?;
+    let stderr = std::fs::File::create(stderr_path).map_err(|e| e.to_string())?;
+    cmd.stderr(std::process::Stdio::from(stderr));
+
+    let process = TokioChildProcess::new(cmd).map_err(|e| format!("Failed to run command: {e}"))?;
+    let (process, mut stdin) = process.split();
+    if let Some(payload) = extract_init_payload(config) {
+        write_mcp_init_payload(
+            &mut stdin,
+            MCP_TEST_SERVER_NAME,
+            &payload,
+            extract_startup_verify_timeout(config),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    let service = ().serve((process, stdin)).await.map_err(|e| format!("Handshake failed: {e}"))?;
+
+    let peer_info = service.peer_info().cloned();
+    let tools = service.list_all_tools().await;
+    if let Err(e) = service.cancel().await {
+        log::warn!("Failed to stop tested MCP server: {}", e);
+    }
+
+    let tools = tools.map_err(|e| format!("Failed to list tools: {e}"))?;
+    Ok(McpServerTestResult {
+        tools: tools
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect(),
+        peer_info,
+    })
+}
+
+/// Makes a server name safe to use as a file name
+pub fn sanitize_server_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    // Avoid names such as "." or ".." that would resolve to a directory
+    if sanitized.chars().all(|c| c == '.') {
+        format!("_{sanitized}")
+    } else {
+        sanitized
+    }
+}
+
+/// Opens `logs/mcp/<server>.log` in append mode for the server's stderr, so history is
+/// kept across restarts. Falls back to `logs/app.log` if the per-server file can't be opened.
+fn open_mcp_server_log_file(
+    app_path: &std::path::Path,
+    name: &str,
+) -> std::io::Result<std::fs::File> {
+    let open_append = |path: &std::path::Path| {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+    };
+
+    let log_dir = app_path.join("logs").join("mcp");
+    let server_log = std::fs::create_dir_all(&log_dir)
+        .and_then(|_| open_append(&log_dir.join(format!("{}.log", sanitize_server_name(name)))));
+
+    server_log.or_else(|err| {
+        log::warn!(
+            "Failed to open log file for MCP server {}, using app.log: {}",
+            name,
+            err
+        );
+        open_append(&app_path.join("logs").join("app.log"))
+    })
+}
+
+/// Returns the last `lines` lines of `logs/mcp/<server>.log`, oldest first
+///
+/// The file is read backwards in chunks from the end, so long-running servers with large
+/// logs are cheap to tail. Returns an empty vec if the server hasn't written a log yet.
+pub fn read_mcp_server_log_tail(
+    app_path: &std::path::Path,
+    name: &str,
+    lines: usize,
+) -> std::io::Result<Vec<String>> {
+    use std::io::{Read, Seek, SeekFrom};
+    const CHUNK_SIZE: u64 = 8 * 1024;
+
+    let path = app_path
+        .join("logs")
+        .join("mcp")
+        .join(format!("{}.log", sanitize_server_name(name)));
+    let mut file = match std::fs::File::open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    if lines == 0 {
+        return Ok(Vec::new());
+    }
+
+    // Read chunks from the end until the buffer holds more than `lines` line breaks,
+    // ignoring the one terminating the last line
+    let mut position = file.seek(SeekFrom::End(0))?;
+    let mut buffer: Vec<u8> = Vec::new();
+    while position > 0 {
+        let chunk_size = CHUNK_SIZE.min(position);
+        position -= chunk_size;
+        file.seek(SeekFrom::Start(position))?;
+        let mut chunk = vec![0; chunk_size as usize];
+        file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&buffer);
+        buffer = chunk;
+
+        let content = buffer.strip_suffix(b"\n").unwrap_or(&buffer);
+        if content.iter().filter(|&&b| b == b'\n').count() >= lines {
+            break;
+        }
+    }
+
+    let content = String::from_utf8_lossy(&buffer);
+    let all_lines: Vec<&str> = content.lines().collect();
+    Ok(all_lines[all_lines.len().saturating_sub(lines)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// Takes the piped stderr of a spawned MCP server, which `TokioChildProcess` would
+/// otherwise keep to itself, so it can be forwarded by `forward_mcp_server_output`
+#[derive(Debug, Clone, Default)]
+struct CaptureStderr(Arc<std::sync::Mutex<Option<ChildStderr>>>);
+
+impl CaptureStderr {
+    fn take(&self) -> Option<ChildStderr> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+impl TokioCommandWrapper for CaptureStderr {
+    fn post_spawn(&mut self, child: &mut Child, _core: &TokioCommandWrap) -> std::io::Result<()> {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = child.stderr.take();
+        Ok(())
+    }
+}
+
+/// Caps how many lines of a server's output are emitted per second. Lines over the
+/// limit are dropped from the events and counted, they still go to the log file.
+#[derive(Debug)]
+pub struct McpOutputRateLimit {
+    max_lines_per_sec: u32,
+    window_start: Instant,
+    emitted: u32,
+    skipped: u64,
+}
+
+impl McpOutputRateLimit {
+    pub fn new(max_lines_per_sec: u32, now: Instant) -> Self {
+        Self {
+            max_lines_per_sec,
+            window_start: now,
+            emitted: 0,
+            skipped: 0,
+        }
+    }
+
+    /// Whether a line read at `now` may be emitted, and how many lines were skipped in
+    /// the window that just ended (0 while it's still running)
+    pub fn admit(&mut self, now: Instant) -> (bool, u64) {
+        let mut skipped = 0;
+        if now.saturating_duration_since(self.window_start) >= Duration::from_secs(1) {
+            skipped = std::mem::take(&mut self.skipped);
+            self.window_start = now;
+            self.emitted = 0;
+        }
+
+        if self.emitted < self.max_lines_per_sec {
+            self.emitted += 1;
+            (true, skipped)
+        } else {
+            self.skipped += 1;
+            (false, skipped)
+        }
+    }
+}
+
+/// Strips the line ending and cuts the line to `max_chars` characters
+pub fn truncate_output_line(line: &str, max_chars: usize) -> String {
+    let line = line.trim_end_matches(['\r', '\n']);
+    match line.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+fn emit_mcp_server_output<R: Runtime>(app: &AppHandle<R>, name: &str, line: String) {
+    let payload = McpServerOutput {
+        server: name.to_string(),
+        line,
+        timestamp: unix_timestamp_ms(),
+    };
+    if let Err(e) = app.emit("mcp_server_output", payload) {
+        log::warn!("Failed to emit mcp_server_output for {}: {}", name, e);
+    }
+}
+
+/// Copies a server's stderr to its log file and emits each line as an `mcp_server_output`
+/// event, until the server closes it (usually by exiting)
+fn forward_mcp_server_output<R: Runtime>(
+    app: AppHandle<R>,
+    name: String,
+    stderr: ChildStderr,
+    mut log_file: Option<std::fs::File>,
+) {
+    use std::io::Write;
+
+    tauri::async_runtime::spawn(async move {
+        let mut reader = BufReader::new(stderr);
+        let mut limit = McpOutputRateLimit::new(MCP_OUTPUT_MAX_LINES_PER_SEC, Instant::now());
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf).await {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    log::warn!("Failed to read stderr of MCP server {}: {}", name, e);
+                    break;
+                }
+            }
+
+            if let Some(file) = log_file.as_mut() {
+                if let Err(e) = file.write_all(&buf) {
+                    log::warn!("Failed to write log file of MCP server {}: {}", name, e);
+                    log_file = None;
+                }
+            }
+
+            let (emit, skipped) = limit.admit(Instant::now());
+            if skipped > 0 {
+                emit_mcp_server_output(&app, &name, format!("[{skipped} lines skipped]"));
+            }
+            if emit {
+                let line = String::from_utf8_lossy(&buf);
+                let line = truncate_output_line(&line, MCP_OUTPUT_MAX_LINE_CHARS);
+                emit_mcp_server_output(&app, &name, line);
+            }
+        }
+        log::debug!("Stderr of MCP server {} closed", name);
+    });
+}
+
 pub fn extract_command_args(
     config: &Value,
 ) -> Option<(String, Vec<Value>, serde_json::Map<String, Value>)> {
@@ -595,54 +1675,445 @@ pub fn extract_command_args(
     Some((command, args, envs))
 }
 
+/// Expands `${VAR}` references in an env value against the process environment
+///
+/// Returns the name of the first variable that isn't set as the error
+pub fn expand_env_vars(value: &str) -> Result<String, String> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find('}') {
+            Some(end) => {
+                let var = &after[..end];
+                expanded.push_str(&env::var(var).map_err(|_| var.to_string())?);
+                rest = &after[end + 1..];
+            }
+            None => {
+                // Unterminated reference, keep it as is
+                break;
+            }
+        }
+    }
+
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Resolves the `cwd` of a server config: expands `${VAR}` references, then a leading
+/// `~` to the home directory
+///
+/// Returns the name of the first variable that isn't set as the error
+pub fn resolve_working_dir(cwd: &str) -> Result<PathBuf, String> {
+    let cwd = expand_env_vars(cwd)?;
+    let home_relative = cwd
+        .strip_prefix('~')
+        .filter(|rest| rest.is_empty() || rest.starts_with(['/', '\\']));
+    match (home_relative, dirs::home_dir()) {
+        (Some(rest), Some(home)) => Ok(home.join(rest.trim_start_matches(['/', '\\']))),
+        _ => Ok(PathBuf::from(cwd)),
+    }
+}
+
 pub fn extract_active_status(config: &Value) -> Option<bool> {
     let obj = config.as_object()?;
     let active = obj.get("active")?.as_bool()?;
     Some(active)
 }
 
+/// Per-server override of the restart limit, read from the optional `maxRestarts` key
+pub fn extract_max_restarts(config: &Value) -> Option<u32> {
+    let obj = config.as_object()?;
+    let max_restarts = obj.get("maxRestarts")?.as_u64()?;
+    Some(u32::try_from(max_restarts).unwrap_or(u32::MAX))
+}
+
+/// Health check interval and timeout for a server, read from the optional
+/// `healthCheckIntervalMs` and `healthCheckTimeoutMs` keys, kept within their limits
+pub fn extract_health_check_settings(config: &Value) -> (Duration, Duration) {
+    let get_ms = |key: &str| {
+        config
+            .get(key)
+            .and_then(Value::as_u64)
+            .map(Duration::from_millis)
+    };
+    (
+        get_ms("healthCheckIntervalMs")
+            .unwrap_or(MCP_HEALTH_CHECK_INTERVAL)
+            .max(MCP_MIN_HEALTH_CHECK_INTERVAL),
+        get_ms("healthCheckTimeoutMs")
+            .unwrap_or(MCP_HEALTH_CHECK_TIMEOUT)
+            .clamp(MCP_MIN_HEALTH_CHECK_TIMEOUT, MCP_MAX_HEALTH_CHECK_TIMEOUT),
+    )
+}
+
+/// How long a server has to stay healthy before its restart count is reset, read from
+/// the optional `restartCountResetMs` key
+pub fn extract_restart_count_reset_window(config: &Value) -> Duration {
+    config
+        .get("restartCountResetMs")
+        .and_then(Value::as_u64)
+        .map(Duration::from_millis)
+        .unwrap_or(MCP_RESTART_COUNT_RESET_WINDOW)
+}
+
+/// How long a tool call may run before it is cancelled, read from the optional
+/// `toolCallTimeoutMs` key and capped at `MCP_MAX_TOOL_CALL_TIMEOUT`
+pub fn extract_tool_call_timeout(config: &Value) -> Duration {
+    config
+        .get("toolCallTimeoutMs")
+        .and_then(Value::as_u64)
+        .map(Duration::from_millis)
+        .unwrap_or(MCP_TOOL_CALL_TIMEOUT)
+        .min(MCP_MAX_TOOL_CALL_TIMEOUT)
+}
+
+/// How long a newly started server has to answer a tool listing before it is considered
+/// broken, read from the optional `startupVerifyTimeoutMs` key
+pub fn extract_startup_verify_timeout(config: &Value) -> Duration {
+    config
+        .get("startupVerifyTimeoutMs")
+        .and_then(Value::as_u64)
+        .map(Duration::from_millis)
+        .unwrap_or(MCP_STARTUP_VERIFY_TIMEOUT)
+}
+
+/// Bootstrap message written to the server's stdin before the MCP handshake, read from
+/// the optional `init` key. Strings are sent as is, JSON values as one compact line.
+pub fn extract_init_payload(config: &Value) -> Option<Vec<u8>> {
+    let mut payload = match config.get("init")? {
+        Value::String(init) => init.clone().into_bytes(),
+        Value::Null => return None,
+        init => serde_json::to_vec(init).ok()?,
+    };
+    if !payload.ends_with(b"\n") {
+        payload.push(b'\n');
+    }
+    Some(payload)
+}
+
+/// Writes the `init` payload of a server to its stdin, giving up after `max_wait` if
+/// the server doesn't read it
+///
+/// A server that exits or closes stdin before reading it fails to start with
+/// `McpError::StartFailed` instead of failing later in the handshake.
+pub async fn write_mcp_init_payload(
+    stdin: &mut (impl AsyncWrite + Unpin),
+    name: &str,
+    payload: &[u8],
+    max_wait: Duration,
+) -> Result<(), McpError> {
+    let write = async {
+        stdin.write_all(payload).await?;
+        stdin.flush().await
+    };
+    let reason = match timeout(max_wait, write).await {
+        Ok(Ok(())) => {
+            log::info!("Sent init payload to MCP server {}", name);
+            return Ok(());
+        }
+        Ok(Err(e)) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+            "closed stdin before reading the init payload".to_string()
+        }
+        Ok(Err(e)) => format!("failed to write the init payload: {e}"),
+        Err(_) => format!(
+            "didn't read the init payload within {}ms",
+            max_wait.as_millis()
+        ),
+    };
+    Err(McpError::StartFailed {
+        server: name.to_string(),
+        reason,
+    })
+}
+
+/// Waits until a newly started server answers a tool listing, polling every
+/// `MCP_STARTUP_VERIFY_POLL_INTERVAL` for at most `max_wait`
+///
+/// Fast servers pass as soon as they answer, while slow ones (e.g. `uvx` downloading a
+/// package on first run) get until `max_wait`. Returns the tools so they don't have to
+/// be listed again. Returns `McpError::QuitImmediately` as soon as the server exits.
+pub async fn verify_mcp_server_started(
+    servers: &Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
+    name: &str,
+    max_wait: Duration,
+) -> Result<Vec<Tool>, McpError> {
+    let deadline = Instant::now() + max_wait;
+    loop {
+        let peer = servers
+            .lock()
+            .await
+            .get(name)
+            .map(|service| service.peer().clone());
+        // A server that exited during startup won't answer, don't wait for the deadline
+        let Some(peer) = peer.filter(|peer| !peer.is_transport_closed()) else {
+            return Err(McpError::QuitImmediately(name.to_string()));
+        };
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match timeout(remaining, peer.list_all_tools()).await {
+            Ok(Ok(tools)) => return Ok(tools),
+            Ok(Err(e)) => log::debug!("MCP server {} not ready yet: {}", name, e),
+            Err(_) => {}
+        }
+
+        if Instant::now() + MCP_STARTUP_VERIFY_POLL_INTERVAL >= deadline {
+            return Err(McpError::VerificationTimeout {
+                server: name.to_string(),
+                timeout_ms: max_wait.as_millis() as u64,
+            });
+        }
+        sleep(MCP_STARTUP_VERIFY_POLL_INTERVAL).await;
+    }
+}
+
+/// Call a tool on a running MCP server, giving up after `timeout` or once `cancel_token`
+/// is cancelled
+///
+/// In both cases the server is sent a cancellation notification for the in-flight
+/// request, and `McpError::ToolCallTimeout` or `McpError::ToolCallCancelled` is returned.
+pub async fn call_mcp_tool_with_timeout(
+    peer: &Peer<RoleClient>,
+    server: &str,
+    tool: &str,
+    arguments: Option<Map<String, Value>>,
+    timeout: Duration,
+    cancel_token: &CancellationToken,
+) -> Result<CallToolResult, McpError> {
+    let failed = |reason: String| McpError::ToolCallFailed {
+        server: server.to_string(),
+        tool: tool.to_string(),
+        reason,
+    };
+
+    let request = ClientRequest::CallToolRequest(CallToolRequest {
+        method: Default::default(),
+        params: CallToolRequestParam {
+            name: tool.to_string().into(),
+            arguments,
+        },
+        extensions: Default::default(),
+    });
+    let options = PeerRequestOptions {
+        timeout: Some(timeout),
+        meta: None,
+    };
+
+    let handle = peer
+        .send_cancellable_request(request, options)
+        .await
+        .map_err(|e| failed(e.to_string()))?;
+
+    // await_response consumes the handle, keep what's needed to cancel the request
+    let peer = handle.peer.clone();
+    let request_id = handle.id.clone();
+
+    // await_response cancels the request on the server when the timeout elapses
+    let response = tokio::select! {
+        response = handle.await_response() => response,
+        _ = cancel_token.cancelled() => {
+            log::info!("Tool call '{}' on MCP server {} cancelled", tool, server);
+            let notification = CancelledNotification {
+                params: CancelledNotificationParam {
+                    request_id,
+                    reason: Some("cancelled by user".to_string()),
+                },
+                method: Default::default(),
+                extensions: Default::default(),
+            };
+            if let Err(e) = peer
+                .send_notification(ClientNotification::CancelledNotification(notification))
+                .await
+            {
+                log::warn!("Failed to notify MCP server {} of cancellation: {}", server, e);
+            }
+            return Err(McpError::ToolCallCancelled {
+                server: server.to_string(),
+                tool: tool.to_string(),
+            });
+        }
+    };
+
+    match response {
+        Ok(ServerResult::CallToolResult(result)) => Ok(result),
+        Ok(_) => Err(failed("unexpected response".to_string())),
+        Err(ServiceError::Timeout { timeout }) => {
+            log::warn!(
+                "Tool call '{}' on MCP server {} timed out after {}ms, request cancelled",
+                tool,
+                server,
+                timeout.as_millis()
+            );
+            Err(McpError::ToolCallTimeout {
+                server: server.to_string(),
+                tool: tool.to_string(),
+                timeout_ms: timeout.as_millis() as u64,
+            })
+        }
+        Err(e) => Err(failed(e.to_string())),
+    }
+}
+
+/// Register an in-flight tool call so that `cancel_mcp_tool_call` can abort it.
+/// The caller must remove the entry with `finish_mcp_tool_call` once the call is over.
+pub async fn register_mcp_tool_call(
+    tool_calls: &Mutex<HashMap<(String, String), CancellationToken>>,
+    server: &str,
+    request_id: &str,
+) -> Result<CancellationToken, McpError> {
+    let mut tool_calls = tool_calls.lock().await;
+    let key = (server.to_string(), request_id.to_string());
+    if tool_calls.contains_key(&key) {
+        return Err(McpError::ToolCallInFlight {
+            server: server.to_string(),
+            request_id: request_id.to_string(),
+        });
+    }
+    let cancel_token = CancellationToken::new();
+    tool_calls.insert(key, cancel_token.clone());
+    Ok(cancel_token)
+}
+
+/// Remove a tool call registered with `register_mcp_tool_call`
+pub async fn finish_mcp_tool_call(
+    tool_calls: &Mutex<HashMap<(String, String), CancellationToken>>,
+    server: &str,
+    request_id: &str,
+) {
+    tool_calls
+        .lock()
+        .await
+        .remove(&(server.to_string(), request_id.to_string()));
+}
+
+/// Cancel an in-flight tool call. Its `call_tool` returns a cancellation result to the
+/// model and removes the entry itself.
+pub async fn cancel_mcp_tool_call(
+    tool_calls: &Mutex<HashMap<(String, String), CancellationToken>>,
+    server: &str,
+    request_id: &str,
+) -> Result<(), McpError> {
+    let tool_calls = tool_calls.lock().await;
+    match tool_calls.get(&(server.to_string(), request_id.to_string())) {
+        Some(cancel_token) => {
+            cancel_token.cancel();
+            Ok(())
+        }
+        None => Err(McpError::ToolCallNotFound {
+            server: server.to_string(),
+            request_id: request_id.to_string(),
+        }),
+    }
+}
+
 /// Restart only servers that were previously active (like cortex restart behavior)
+///
+/// Returns immediately, the restart continues in the background. Progress is reported
+/// through the events described in `restart_mcp_servers_and_wait`.
 pub async fn restart_active_mcp_servers<R: Runtime>(
     app: &AppHandle<R>,
     servers_state: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
-) -> Result<(), String> {
-    let app_state = app.state::<AppState>();
-    let active_servers = app_state.mcp_active_servers.lock().await;
+) -> Result<(), McpError> {
+    let active_servers = app
+        .state::<AppState>()
+        .mcp_active_servers
+        .lock()
+        .await
+        .clone();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        restart_mcp_servers_and_wait(&app, servers_state, active_servers).await;
+    });
+    Ok(())
+}
 
+/// Restarts the given servers and waits for all of them
+///
+/// Emits `mcp_restart_progress` as each server starts or fails, and a final
+/// `mcp_restart_complete` with the counts, which are also returned. Like
+/// `run_mcp_commands`, only a few servers are started at a time.
+pub async fn restart_mcp_servers_and_wait<R: Runtime>(
+    app: &AppHandle<R>,
+    servers_state: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
+    active_servers: HashMap<String, Value>,
+) -> McpRestartSummary {
     log::info!(
         "Restarting {} previously active MCP servers",
         active_servers.len()
     );
 
-    for (name, config) in active_servers.iter() {
-        log::info!("Restarting MCP server: {}", name);
+    let restart_tasks: Vec<_> = active_servers
+        .into_iter()
+        .map(|(name, config)| {
+            log::info!("Restarting MCP server: {}", name);
+            let app = app.clone();
+            let servers_state = servers_state.clone();
+            async move {
+                let result = start_mcp_server_with_restart(
+                    app.clone(),
+                    servers_state,
+                    name.clone(),
+                    config,
+                    Some(MCP_STARTUP_MAX_RESTARTS),
+                )
+                .await;
 
-        // Start server with restart monitoring - spawn async task
-        let app_clone = app.clone();
-        let servers_clone = servers_state.clone();
-        let name_clone = name.clone();
-        let config_clone = config.clone();
+                let progress = match &result {
+                    Ok(_) => McpRestartProgress {
+                        server: name,
+                        status: McpRestartStatus::Started,
+                        error: None,
+                    },
+                    Err(e) => McpRestartProgress {
+                        server: name,
+                        status: McpRestartStatus::Failed,
+                        error: Some(e.to_string()),
+                    },
+                };
+                if let Err(e) = app.emit("mcp_restart_progress", &progress) {
+                    log::error!("Failed to emit mcp_restart_progress event: {e}");
+                }
+                progress.status
+            }
+        })
+        .collect();
+
+    let mut summary = McpRestartSummary::default();
+    for result in join_with_concurrency_limit(restart_tasks, MCP_MAX_CONCURRENT_STARTUPS).await {
+        match result {
+            Ok(McpRestartStatus::Started) => summary.successful += 1,
+            Ok(McpRestartStatus::Failed) => summary.failed += 1,
+            Err(e) => {
+                log::error!("Failed to join restart task: {}", e);
+                summary.failed += 1;
+            }
+        }
+    }
 
-        tauri::async_runtime::spawn(async move {
-            let _ = start_mcp_server_with_restart(
-                app_clone,
-                servers_clone,
-                name_clone,
-                config_clone,
-                Some(3), // Default max restarts for startup
-            )
-            .await;
-        });
+    log::info!(
+        "MCP server restart complete: {} successful, {} failed",
+        summary.successful,
+        summary.failed
+    );
+    if let Err(e) = app.emit("mcp_restart_complete", summary) {
+        log::error!("Failed to emit mcp_restart_complete event: {e}");
     }
 
-    Ok(())
+    summary
 }
 
 pub async fn clean_up_mcp_servers(state: State<'_, AppState>) {
     log::info!("Cleaning up MCP servers");
 
+    // Stop the monitors first, so they don't restart the servers being stopped
+    for (_, handle) in state.mcp_monitor_tasks.lock().await.drain() {
+        handle.abort();
+    }
+
     // Stop all running MCP servers
-    let _ = stop_mcp_servers(state.mcp_servers.clone()).await;
+    let _ = stop_mcp_servers(state.mcp_servers.clone(), state.mcp_server_pids.clone()).await;
 
     // Clear active servers and restart counts
     {
@@ -653,20 +2124,567 @@ pub async fn clean_up_mcp_servers(state: State<'_, AppState>) {
         let mut restart_counts = state.mcp_restart_counts.lock().await;
         restart_counts.clear();
     }
+    {
+        let mut tool_cache = state.mcp_tool_cache.lock().await;
+        tool_cache.clear();
+    }
     log::info!("MCP servers cleaned up successfully");
 }
 
 pub async fn stop_mcp_servers(
     servers_state: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
-) -> Result<(), String> {
+    server_pids: Arc<Mutex<HashMap<String, u32>>>,
+) -> Result<(), McpError> {
     let mut servers_map = servers_state.lock().await;
     let keys: Vec<String> = servers_map.keys().cloned().collect();
+    let mut first_error = None;
     for key in keys {
         if let Some(service) = servers_map.remove(&key) {
-            service.cancel().await.map_err(|e| e.to_string())?;
+            // Keep stopping the remaining servers if one fails
+            if let Err(e) = cancel_mcp_service(&server_pids, &key, service).await {
+                log::error!("{}", e);
+                first_error.get_or_insert(e);
+            }
         }
     }
     drop(servers_map); // Release the lock after stopping
+
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Cancel a running MCP service, waiting up to `MCP_SHUTDOWN_TIMEOUT` for it to stop.
+/// If it doesn't, its process is killed so a wedged server can't hang shutdown.
+pub async fn cancel_mcp_service(
+    server_pids: &Arc<Mutex<HashMap<String, u32>>>,
+    name: &str,
+    service: RunningService<RoleClient, ()>,
+) -> Result<(), McpError> {
+    let pid = server_pids.lock().await.remove(name);
+
+    match timeout(MCP_SHUTDOWN_TIMEOUT, service.cancel()).await {
+        Ok(result) => result.map(|_| ()).map_err(|e| McpError::StopFailed {
+            server: name.to_string(),
+            reason: e.to_string(),
+        }),
+        Err(_) => {
+            // Dropping the timed out cancel future drops the service, but the child is only
+            // killed (kill_on_drop) once rmcp's transport task unwinds, which a wedged
+            // server may never let happen. Kill the process directly instead.
+            log::warn!(
+                "MCP server {} did not stop within {} seconds, forcing kill",
+                name,
+                MCP_SHUTDOWN_TIMEOUT.as_secs()
+            );
+            match pid {
+                Some(pid) => force_kill_process(pid),
+                None => log::error!("No process id recorded for MCP server {}", name),
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Samples the memory and CPU usage of MCP server processes, keyed by server name
+///
+/// CPU usage is measured between two refreshes, so this blocks for
+/// `sysinfo::MINIMUM_CPU_UPDATE_INTERVAL` and should run on a blocking thread.
+/// Servers whose process has exited map to `None`.
+pub fn sample_mcp_process_stats(
+    server_pids: &HashMap<String, u32>,
+) -> HashMap<String, Option<McpProcessStats>> {
+    let pids: Vec<Pid> = server_pids
+        .values()
+        .map(|pid| Pid::from_u32(*pid))
+        .collect();
+    let refresh_kind = ProcessRefreshKind::nothing().with_memory().with_cpu();
+
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&pids), true, refresh_kind);
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&pids), true, refresh_kind);
+
+    server_pids
+        .iter()
+        .map(|(name, pid)| {
+            let stats = system
+                .process(Pid::from_u32(*pid))
+                .filter(|process| process.status() != ProcessStatus::Zombie)
+                .map(|process| McpProcessStats {
+                    pid: *pid,
+                    memory_bytes: process.memory(),
+                    cpu_usage: process.cpu_usage(),
+                });
+            (name.clone(), stats)
+        })
+        .collect()
+}
+
+fn force_kill_process(pid: u32) {
+    #[cfg(unix)]
+    {
+        use nix::{
+            sys::signal::{killpg, Signal},
+            unistd::Pid,
+        };
+        // The server leads its own process group (see `build_mcp_command`), killing the
+        // group takes down the processes it spawned as well
+        match killpg(Pid::from_raw(pid as i32), Signal::SIGKILL) {
+            Ok(_) => log::info!("Killed process group {}", pid),
+            Err(e) => log::error!("Failed to kill process group {}: {}", pid, e),
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        match std::process::Command::new("taskkill")
+            .args(["/F", "/T", "/PID", &pid.to_string()])
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW: prevents shell window on Windows
+            .status()
+        {
+            Ok(status) if status.success() => log::info!("Killed process {}", pid),
+            Ok(status) => log::error!(
+                "Failed to kill process {}: taskkill exited with {}",
+                pid,
+                status
+            ),
+            Err(e) => log::error!("Failed to kill process {}: {}", pid, e),
+        }
+    }
+}
+
+/// Lists the tools of a running server and stores them in the tool cache
+pub async fn refresh_mcp_tool_cache(
+    servers_state: &Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
+    tool_cache: &Arc<Mutex<HashMap<String, Vec<Tool>>>>,
+    name: &str,
+) -> Result<Vec<Tool>, McpError> {
+    // Drop the stale entry first so a failed refresh doesn't leave it behind
+    tool_cache.lock().await.remove(name);
+
+    // Query a cloned peer, so the servers map isn't locked while waiting on a slow server
+    let peer = servers_state
+        .lock()
+        .await
+        .get(name)
+        .map(|service| service.peer().clone())
+        .ok_or_else(|| McpError::ServerNotFound(name.to_string()))?;
+    let tools = timeout(MCP_TOOL_CALL_TIMEOUT, peer.list_all_tools())
+        .await
+        .map_err(|_| McpError::ListToolsFailed {
+            server: name.to_string(),
+            reason: format!(
+                "timed out after {} seconds",
+                MCP_TOOL_CALL_TIMEOUT.as_secs()
+            ),
+        })?
+        .map_err(|e| McpError::ListToolsFailed {
+            server: name.to_string(),
+            reason: e.to_string(),
+        })?;
+
+    tool_cache
+        .lock()
+        .await
+        .insert(name.to_string(), tools.clone());
+    Ok(tools)
+}
+
+/// Returns the tools of a server from the tool cache, listing them if they aren't cached
+pub async fn get_cached_tools_or_refresh(
+    servers_state: &Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
+    tool_cache: &Arc<Mutex<HashMap<String, Vec<Tool>>>>,
+    name: &str,
+) -> Result<Vec<Tool>, McpError> {
+    if let Some(tools) = tool_cache.lock().await.get(name) {
+        return Ok(tools.clone());
+    }
+    refresh_mcp_tool_cache(servers_state, tool_cache, name).await
+}
+
+/// Returns the JSON schema of each tool's input by tool name, from the tool cache
+pub async fn get_mcp_tool_schemas(
+    servers_state: &Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
+    tool_cache: &Arc<Mutex<HashMap<String, Vec<Tool>>>>,
+    name: &str,
+) -> Result<HashMap<String, Value>, McpError> {
+    let tools = get_cached_tools_or_refresh(servers_state, tool_cache, name).await?;
+    Ok(tools
+        .into_iter()
+        .map(|tool| {
+            let schema = Value::Object(tool.input_schema.as_ref().clone());
+            (tool.name.into_owned(), schema)
+        })
+        .collect())
+}
+
+/// Lists the tools of every connected MCP server concurrently, at most
+/// `MCP_MAX_CONCURRENT_TOOL_LISTINGS` at a time
+///
+/// A server that fails or times out gets an `error` in its listing instead of failing the
+/// whole catalog. Listings are sorted by server name and include how long each one took.
+pub async fn list_all_mcp_tools(
+    servers: &Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>>,
+) -> McpToolCatalog {
+    // Query cloned peers, so the servers map isn't locked while waiting on slow servers
+    let peers: Vec<_> = servers
+        .lock()
+        .await
+        .iter()
+        .map(|(name, service)| (name.clone(), service.peer().clone()))
+        .collect();
+
+    let tasks: Vec<_> = peers
+        .into_iter()
+        .map(|(name, peer)| async move {
+            let started = Instant::now();
+            let result = match timeout(MCP_TOOL_CALL_TIMEOUT, peer.list_all_tools()).await {
+                Ok(result) => result.map_err(|e| e.to_string()),
+                Err(_) => Err(format!(
+                    "Listing tools timed out after {} seconds",
+                    MCP_TOOL_CALL_TIMEOUT.as_secs()
+                )),
+            };
+            (name, started.elapsed(), result)
+        })
+        .collect();
+
+    let mut catalog = McpToolCatalog::default();
+    for result in join_with_concurrency_limit(tasks, MCP_MAX_CONCURRENT_TOOL_LISTINGS).await {
+        let (server, elapsed, result) = match result {
+            Ok(listing) => listing,
+            Err(e) => {
+                log::error!("Tool listing task panicked: {}", e);
+                continue;
+            }
+        };
+        let mut listing = McpServerToolListing {
+            server: server.clone(),
+            tool_count: 0,
+            elapsed_ms: elapsed.as_millis() as u64,
+            error: None,
+        };
+        match result {
+            Ok(tools) => {
+                listing.tool_count = tools.len();
+                catalog
+                    .tools
+                    .extend(tools.into_iter().map(|tool| McpCatalogTool {
+                        server: server.clone(),
+                        tool,
+                    }));
+            }
+            Err(e) => {
+                log::warn!("Failed to list tools of MCP server {}: {}", server, e);
+                listing.error = Some(e);
+            }
+        }
+        catalog.servers.push(listing);
+    }
+
+    catalog.servers.sort_by(|a, b| a.server.cmp(&b.server));
+    catalog.tools.sort_by(|a, b| {
+        a.server
+            .cmp(&b.server)
+            .then_with(|| a.tool.name.cmp(&b.tool.name))
+    });
+    catalog
+}
+
+/// Stops a single MCP server and marks it as deactivated so it won't be restarted
+pub async fn deactivate_mcp_server(state: &AppState, name: &str) -> Result<(), McpError> {
+    log::info!("Deactivating MCP server: {}", name);
+
+    // First, mark server as manually deactivated to prevent restart
+    abort_server_monitoring_task(state, name).await;
+
+    // Remove from active servers list to prevent restart
+    {
+        let mut active_servers = state.mcp_active_servers.lock().await;
+        active_servers.remove(name);
+        log::info!("Removed MCP server {} from active servers list", name);
+    }
+
+    // Mark as not successfully connected to prevent restart logic
+    {
+        let mut connected = state.mcp_successfully_connected.lock().await;
+        connected.insert(name.to_string(), false);
+        log::info!("Marked MCP server {} as not successfully connected", name);
+    }
+
+    // Reset restart count
+    {
+        let mut counts = state.mcp_restart_counts.lock().await;
+        counts.remove(name);
+        log::info!("Reset restart count for MCP server {}", name);
+    }
+
+    state.mcp_tool_cache.lock().await.remove(name);
+
+    // Now remove and stop the server
+    let servers = state.mcp_servers.clone();
+    let mut servers_map = servers.lock().await;
+
+    let service = servers_map
+        .remove(name)
+        .ok_or_else(|| McpError::ServerNotFound(name.to_string()))?;
+
+    // Release the lock before calling cancel
+    drop(servers_map);
+
+    cancel_mcp_service(&state.mcp_server_pids, name, service).await?;
+    log::info!("Server {name} stopped successfully and marked as deactivated.");
+    record_mcp_event(
+        &state.mcp_events,
+        name,
+        McpLifecycleEventKind::Stopped,
+        "Deactivated",
+    )
+    .await;
+    Ok(())
+}
+
+/// Stops a single MCP server on request, whether or not it is running. In-flight tool
+/// calls are cancelled first, then the server is deactivated like in `deactivate_mcp_server`.
+/// It is also remembered as stopped: config reloads leave it alone, and `run_mcp_commands`
+/// only starts it again if its config explicitly sets `active: true`. Returns whether a
+/// running server was stopped.
+pub async fn drain_mcp_server(state: &AppState, name: &str) -> Result<bool, McpError> {
+    state
+        .mcp_stopped_servers
+        .lock()
+        .await
+        .insert(name.to_string());
+
+    for ((server, request_id), cancel_token) in state.mcp_tool_calls.lock().await.iter() {
+        if server == name {
+            log::info!("Cancelling tool call {request_id} on stopping MCP server {name}");
+            cancel_token.cancel();
+        }
+    }
+
+    match deactivate_mcp_server(state, name).await {
+        Ok(()) => Ok(true),
+        Err(McpError::ServerNotFound(_)) => {
+            log::info!("MCP server {name} is not running, nothing to stop");
+            Ok(false)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Combines the MCP state maps into a status record per known server, sorted by name
+///
+/// A server is known if it is running, active in config, or has connected or restarted
+/// before. Active servers that aren't running yet are starting or restarting, so they
+/// are reported as connecting. Only reads state, no requests are sent to the servers.
+pub async fn get_mcp_server_statuses(state: &AppState) -> Vec<McpServerStatus> {
+    let running: HashMap<String, Option<ServerInfo>> = state
+        .mcp_servers
+        .lock()
+        .await
+        .iter()
+        .map(|(name, service)| (name.clone(), service.peer_info().cloned()))
+        .collect();
+    let active_servers = state.mcp_active_servers.lock().await.clone();
+    let restart_counts = state.mcp_restart_counts.lock().await.clone();
+    let connected = state.mcp_successfully_connected.lock().await.clone();
+
+    let mut names: Vec<&String> = running
+        .keys()
+        .chain(active_servers.keys())
+        .chain(restart_counts.keys())
+        .chain(connected.keys())
+        .collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let state = if running.contains_key(name) {
+                McpServerState::Running
+            } else if active_servers.contains_key(name) {
+                McpServerState::Connecting
+            } else {
+                McpServerState::Stopped
+            };
+            McpServerStatus {
+                name: name.clone(),
+                state,
+                restart_count: restart_counts.get(name).copied().unwrap_or(0),
+                successfully_connected: connected.get(name).copied().unwrap_or(false),
+                peer_info: running.get(name).cloned().flatten(),
+            }
+        })
+        .collect()
+}
+
+/// Compare the active server configs against a freshly loaded `mcpServers` map
+///
+/// Servers that are new or newly active are started, removed or deactivated ones are
+/// stopped, and active servers whose config changed are restarted. Servers in `stopped`
+/// were stopped by hand and aren't started again, whatever their config says.
+pub fn diff_mcp_configs(
+    active_servers: &HashMap<String, Value>,
+    server_map: &Map<String, Value>,
+    stopped: &HashSet<String>,
+) -> McpConfigDiff {
+    let mut diff = McpConfigDiff::default();
+
+    for (name, config) in server_map {
+        if extract_active_status(config) == Some(false) || stopped.contains(name) {
+            continue;
+        }
+        match active_servers.get(name) {
+            None => diff.started.push(name.clone()),
+            Some(active_config) if active_config != config => diff.restarted.push(name.clone()),
+            Some(_) => {}
+        }
+    }
+
+    for name in active_servers.keys() {
+        let still_active = server_map
+            .get(name)
+            .is_some_and(|config| extract_active_status(config) != Some(false));
+        if !still_active {
+            diff.stopped.push(name.clone());
+        }
+    }
+
+    diff.started.sort();
+    diff.stopped.sort();
+    diff.restarted.sort();
+    diff
+}
+
+/// Re-reads mcp_config.json and starts, stops or restarts servers to match it
+pub async fn reload_mcp_config<R: Runtime>(app: &AppHandle<R>) -> Result<McpConfigDiff, McpError> {
+    let server_map = filter_invalid_mcp_servers(app, read_mcp_server_map(app)?);
+    let app_state = app.state::<AppState>();
+    let diff = {
+        let active_servers = app_state.mcp_active_servers.lock().await;
+        let stopped = app_state.mcp_stopped_servers.lock().await;
+        diff_mcp_configs(&active_servers, &server_map, &stopped)
+    };
+
+    if diff.is_empty() {
+        log::info!("MCP config reloaded, no changes");
+        return Ok(diff);
+    }
+
+    for name in diff.stopped.iter().chain(diff.restarted.iter()) {
+        if let Err(e) = deactivate_mcp_server(&app_state, name).await {
+            log::warn!("Failed to stop MCP server {} on config reload: {}", name, e);
+        }
+    }
+
+    for name in diff.started.iter().chain(diff.restarted.iter()) {
+        let app_clone = app.clone();
+        let servers_clone = app_state.mcp_servers.clone();
+        let name_clone = name.clone();
+        let config_clone = server_map[name].clone();
+
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = start_mcp_server_with_restart(
+                app_clone,
+                servers_clone,
+                name_clone.clone(),
+                config_clone,
+                Some(MCP_STARTUP_MAX_RESTARTS),
+            )
+            .await
+            {
+                log::error!(
+                    "Failed to start MCP server {} on config reload: {}",
+                    name_clone,
+                    e
+                );
+            }
+        });
+    }
+
+    log::info!("MCP config reloaded: {:?}", diff);
+    app.emit("mcp_config_reloaded", &diff)
+        .map_err(McpError::Emit)?;
+
+    Ok(diff)
+}
+
+/// Hash of an mcp_config.json's contents, see [`is_saved_mcp_config`]
+pub fn hash_mcp_config(contents: &str) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether the config at `path` is the one last written by `save_mcp_configs`, whose
+/// hash is `saved_hash`
+pub async fn is_saved_mcp_config(saved_hash: &Mutex<Option<u64>>, path: &std::path::Path) -> bool {
+    let Some(saved_hash) = *saved_hash.lock().await else {
+        return false;
+    };
+    std::fs::read_to_string(path).is_ok_and(|contents| hash_mcp_config(&contents) == saved_hash)
+}
+
+/// Watch mcp_config.json and reload MCP servers when it changes
+///
+/// Rapid successive writes are debounced so a single save triggers one reload. Saves
+/// made by Jan itself through `save_mcp_configs` are ignored, the frontend applies
+/// those on its own.
+pub fn watch_mcp_config<R: Runtime>(app: AppHandle<R>) -> Result<(), McpError> {
+    let app_path = get_jan_data_folder_path(app.clone());
+    let config_path = app_path.join("mcp_config.json");
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let mut watcher =
+        notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+            Ok(event) => {
+                let is_config = event.paths.iter().any(|path| {
+                    path.file_name()
+                        .is_some_and(|name| name == "mcp_config.json")
+                });
+                if is_config && matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    let _ = tx.send(());
+                }
+            }
+            Err(e) => log::error!("MCP config watcher error: {}", e),
+        })
+        .map_err(McpError::Watch)?;
+
+    // Watch the folder rather than the file, editors often save by replacing the file
+    watcher
+        .watch(&app_path, RecursiveMode::NonRecursive)
+        .map_err(McpError::Watch)?;
+
+    tauri::async_runtime::spawn(async move {
+        // The watcher stops when dropped, keep it alive for the lifetime of this task
+        let _watcher = watcher;
+
+        while rx.recv().await.is_some() {
+            // Wait until no further changes arrive within the debounce window
+            loop {
+                match timeout(MCP_CONFIG_RELOAD_DEBOUNCE, rx.recv()).await {
+                    Ok(Some(_)) => continue,
+                    Ok(None) => return,
+                    Err(_) => break,
+                }
+            }
+
+            let saved_hash = app.state::<AppState>().mcp_saved_config_hash.clone();
+            if is_saved_mcp_config(&saved_hash, &config_path).await {
+                log::debug!("Ignoring mcp_config.json change saved by Jan");
+                continue;
+            }
+            if let Err(e) = reload_mcp_config(&app).await {
+                log::error!("Failed to reload MCP config: {}", e);
+            }
+        }
+    });
+
     Ok(())
 }
 
@@ -700,11 +2718,20 @@ pub async fn spawn_server_monitoring_task<R: Runtime>(
     let servers_clone = servers_state.clone();
     let name_clone = name.clone();
     let config_clone = config.clone();
+    let (health_check_interval, health_check_timeout) = extract_health_check_settings(&config);
+    let restart_count_reset_window = extract_restart_count_reset_window(&config);
 
-    tauri::async_runtime::spawn(async move {
+    let handle = tauri::async_runtime::spawn(async move {
         // Monitor the server using RunningService's JoinHandle<QuitReason>
-        let quit_reason =
-            monitor_mcp_server_handle(servers_clone.clone(), name_clone.clone()).await;
+        let quit_reason = monitor_mcp_server_handle(
+            app_clone.clone(),
+            servers_clone.clone(),
+            name_clone.clone(),
+            health_check_interval,
+            health_check_timeout,
+            restart_count_reset_window,
+        )
+        .await;
 
         log::info!(
             "MCP server {} quit with reason: {:?}",
@@ -727,6 +2754,71 @@ pub async fn spawn_server_monitoring_task<R: Runtime>(
             .await;
         }
     });
+
+    // Keep the handle so the monitor (and any restart loop it runs) can be stopped when
+    // the server is deactivated or restarted manually
+    let app_state = app.state::<AppState>();
+    let previous = app_state
+        .mcp_monitor_tasks
+        .lock()
+        .await
+        .insert(name, handle);
+    if let Some(previous) = previous {
+        previous.abort();
+    }
+}
+
+/// Stop the monitoring task of a server, so it doesn't restart the server on its own
+pub async fn abort_server_monitoring_task(state: &AppState, name: &str) {
+    if let Some(handle) = state.mcp_monitor_tasks.lock().await.remove(name) {
+        handle.abort();
+        log::info!("Stopped monitoring MCP server {}", name);
+    }
+}
+
+/// Stop the running instance of a server before starting it again: its monitoring task
+/// is aborted so it isn't restarted behind our back, and its restart count is reset
+async fn stop_running_mcp_instance(app_state: &AppState, name: &str) {
+    abort_server_monitoring_task(app_state, name).await;
+
+    let service = app_state.mcp_servers.lock().await.remove(name);
+    if let Some(service) = service {
+        if let Err(e) = cancel_mcp_service(&app_state.mcp_server_pids, name, service).await {
+            log::warn!("{}", e);
+        }
+    }
+    app_state.mcp_tool_cache.lock().await.remove(name);
+    reset_restart_count(&app_state.mcp_restart_counts, name).await;
+}
+
+/// Restart a single active server with its stored config
+///
+/// The running instance, if any, is stopped and its restart count reset before
+/// starting it again. Returns `ServerNotFound` if the server isn't active.
+pub async fn restart_single_mcp_server<R: Runtime>(
+    app: AppHandle<R>,
+    name: String,
+) -> Result<(), McpError> {
+    let app_state = app.state::<AppState>();
+    let config = app_state
+        .mcp_active_servers
+        .lock()
+        .await
+        .get(&name)
+        .cloned()
+        .ok_or_else(|| McpError::ServerNotFound(name.clone()))?;
+
+    log::info!("Manually restarting MCP server {}", name);
+    stop_running_mcp_instance(&app_state, &name).await;
+
+    start_mcp_server_with_restart(
+        app.clone(),
+        app_state.mcp_servers.clone(),
+        name,
+        config,
+        Some(MCP_STARTUP_MAX_RESTARTS),
+    )
+    .await
 }
 
 /// Determine if a server should be restarted based on its connection status and quit reason
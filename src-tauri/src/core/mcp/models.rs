@@ -0,0 +1,212 @@
+use rmcp::model::{ServerInfo, Tool};
+
+/// Errors returned by the MCP helpers. Tauri commands convert them to strings for the frontend.
+#[derive(Debug, thiserror::Error)]
+pub enum McpError {
+    #[error("Failed to read config file: {0}")]
+    ConfigRead(#[source] std::io::Error),
+    #[error("Failed to parse config: {0}")]
+    ConfigParse(#[source] serde_json::Error),
+    #[error("{0}")]
+    ConfigInvalid(String),
+    #[error("Environment variable {var} used in {key} for MCP server {server} is not set")]
+    MissingEnvVar {
+        server: String,
+        key: String,
+        var: String,
+    },
+    #[error("Working directory {path} of MCP server {server} does not exist")]
+    WorkingDirNotFound { server: String, path: String },
+    #[error("Failed to run command {server}: {source}")]
+    SpawnFailed {
+        server: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Failed to start MCP server {server}: {reason}")]
+    StartFailed { server: String, reason: String },
+    #[error("MCP server {0} quit immediately after starting")]
+    QuitImmediately(String),
+    #[error("MCP server {0} failed verification after startup")]
+    VerificationFailed(String),
+    #[error("MCP server {server} did not answer within {timeout_ms}ms after starting")]
+    VerificationTimeout { server: String, timeout_ms: u64 },
+    #[error("Server {0} not found")]
+    ServerNotFound(String),
+    #[error("Tool {tool} not found on MCP server {server}")]
+    ToolNotFound { server: String, tool: String },
+    #[error("Failed to list tools of MCP server {server}: {reason}")]
+    ListToolsFailed { server: String, reason: String },
+    #[error("Tool call '{tool}' on MCP server {server} timed out after {timeout_ms}ms")]
+    ToolCallTimeout {
+        server: String,
+        tool: String,
+        timeout_ms: u64,
+    },
+    #[error("Tool call '{tool}' on MCP server {server} was cancelled")]
+    ToolCallCancelled { server: String, tool: String },
+    #[error("Tool call {request_id} is already in flight on MCP server {server}")]
+    ToolCallInFlight { server: String, request_id: String },
+    #[error("No in-flight tool call {request_id} on MCP server {server}")]
+    ToolCallNotFound { server: String, request_id: String },
+    #[error("Tool call '{tool}' on MCP server {server} failed: {reason}")]
+    ToolCallFailed {
+        server: String,
+        tool: String,
+        reason: String,
+    },
+    #[error("Failed to stop MCP server {server}: {reason}")]
+    StopFailed { server: String, reason: String },
+    #[error("Failed to watch MCP config: {0}")]
+    Watch(#[source] notify::Error),
+    #[error("Failed to emit event: {0}")]
+    Emit(#[source] tauri::Error),
+}
+
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpHealthStatus {
+    Healthy,
+    Degraded,
+    Unreachable,
+}
+
+/// Payload of the `mcp_server_health` event
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct McpServerHealth {
+    pub name: String,
+    pub status: McpHealthStatus,
+    pub latency_ms: u64,
+    pub timestamp: u64, // milliseconds since the Unix epoch
+}
+
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpServerState {
+    Running,
+    Connecting,
+    Stopped,
+}
+
+/// Payload of the `mcp_server_output` event, a line the server wrote to stderr
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct McpServerOutput {
+    pub server: String,
+    pub line: String,
+    pub timestamp: u64, // milliseconds since the Unix epoch
+}
+
+/// State of a known MCP server as tracked in `AppState`, without probing it
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct McpServerStatus {
+    pub name: String,
+    pub state: McpServerState,
+    pub restart_count: u32,
+    pub successfully_connected: bool,
+    pub peer_info: Option<ServerInfo>, // from the last handshake, while running
+}
+
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum McpLifecycleEventKind {
+    Start,
+    Connected,
+    HealthFail,
+    Restart,
+    CircuitOpen,
+    GiveUp,
+    Stopped,
+}
+
+/// An entry of the MCP lifecycle event log returned by `get_mcp_events`
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct McpLifecycleEvent {
+    pub server: String,
+    pub kind: McpLifecycleEventKind,
+    pub message: String,
+    pub timestamp: u64, // milliseconds since the Unix epoch
+}
+
+/// Resource usage of an MCP server process, returned by `get_mcp_process_stats`
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct McpProcessStats {
+    pub pid: u32,
+    pub memory_bytes: u64, // resident set size
+    pub cpu_usage: f32,    // percent of one core, can exceed 100 for multi-threaded servers
+}
+
+/// Whether a server came back up during a restart of all active servers
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpRestartStatus {
+    Started,
+    Failed,
+}
+
+/// Payload of the `mcp_restart_progress` event, emitted as each server finishes restarting
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct McpRestartProgress {
+    pub server: String,
+    pub status: McpRestartStatus,
+    pub error: Option<String>,
+}
+
+/// Payload of the `mcp_restart_complete` event, once every server has been restarted
+#[derive(serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct McpRestartSummary {
+    pub successful: usize,
+    pub failed: usize,
+}
+
+/// Outcome of a successful `test_mcp_server` run
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct McpServerTestResult {
+    pub tools: Vec<String>,
+    pub peer_info: Option<ServerInfo>,
+}
+
+/// A tool in the catalog returned by `list_all_mcp_tools`, with the server providing it
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct McpCatalogTool {
+    pub server: String,
+    #[serde(flatten)]
+    pub tool: Tool,
+}
+
+/// How listing the tools of one server went, for `list_all_mcp_tools`
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct McpServerToolListing {
+    pub server: String,
+    pub tool_count: usize,
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Tools of all connected MCP servers, in one flat list
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct McpToolCatalog {
+    pub tools: Vec<McpCatalogTool>,
+    pub servers: Vec<McpServerToolListing>,
+}
+
+/// A problem found while validating a server entry in mcp_config.json
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct McpConfigError {
+    pub server: String,
+    pub field: String,
+    pub message: String,
+}
+
+/// Changes applied to the running MCP servers when mcp_config.json is reloaded
+#[derive(serde::Serialize, Clone, Debug, Default, PartialEq)]
+pub struct McpConfigDiff {
+    pub started: Vec<String>,
+    pub stopped: Vec<String>,
+    pub restarted: Vec<String>,
+}
+
+impl McpConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.started.is_empty() && self.stopped.is_empty() && self.restarted.is_empty()
+    }
+}
@@ -1,16 +1,48 @@
-use super::helpers::run_mcp_commands;
-use crate::core::app::commands::get_jan_data_folder_path;
+use super::commands::call_tool;
+use super::constants::{
+    MCP_BASE_RESTART_DELAY_MS, MCP_CIRCUIT_BREAKER_THRESHOLD, MCP_CIRCUIT_BREAKER_WINDOW,
+    MCP_EVENT_LOG_CAPACITY, MCP_HEALTH_CHECK_INTERVAL, MCP_HEALTH_CHECK_TIMEOUT,
+    MCP_MAX_HEALTH_CHECK_TIMEOUT, MCP_MAX_RESTART_DELAY_MS, MCP_MAX_TOOL_CALL_TIMEOUT,
+    MCP_MIN_HEALTH_CHECK_INTERVAL, MCP_MIN_HEALTH_CHECK_TIMEOUT, MCP_RESTART_COUNT_RESET_WINDOW,
+    MCP_STARTUP_VERIFY_TIMEOUT, MCP_TOOL_CALL_TIMEOUT,
+};
+use super::helpers::{
+    calculate_exponential_backoff_delay_with_rng, cancel_mcp_tool_call, clean_up_mcp_servers,
+    diff_mcp_configs, drain_mcp_server, expand_env_vars, extract_health_check_settings,
+    extract_init_payload, extract_max_restarts, extract_restart_count_reset_window,
+    extract_startup_verify_timeout, extract_tool_call_timeout, finish_mcp_tool_call,
+    get_mcp_server_statuses, get_mcp_tool_schemas, hash_mcp_config, is_saved_mcp_config,
+    join_with_concurrency_limit, list_all_mcp_tools, query_mcp_events, read_mcp_server_log_tail,
+    record_mcp_event, register_mcp_tool_call, reload_mcp_config, resolve_working_dir,
+    restart_mcp_servers_and_wait, run_mcp_commands, sample_mcp_process_stats, sanitize_server_name,
+    select_mcp_servers_to_start, set_mcp_monitoring_paused, start_restart_loop,
+    test_mcp_server_config, truncate_output_line, validate_mcp_server_config,
+    verify_mcp_server_started, wait_while_mcp_monitoring_paused, write_mcp_init_payload,
+    McpCircuitBreaker, McpOutputRateLimit,
+};
+use super::models::{
+    McpError, McpHealthStatus, McpLifecycleEventKind, McpRestartSummary, McpServerHealth,
+    McpServerState,
+};
+use crate::core::{app::commands::get_jan_data_folder_path, state::AppState};
+use rand::{rngs::StdRng, SeedableRng};
 use rmcp::{service::RunningService, RoleClient};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::Write;
 use std::sync::Arc;
-use tauri::test::mock_app;
-use tokio::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{test::mock_app, Manager};
+use tokio::{sync::Mutex, time::timeout};
+
+// Tests that write mcp_config.json share the mock app's data folder
+static MCP_CONFIG_FILE_LOCK: Mutex<()> = Mutex::const_new(());
 
 #[tokio::test]
 async fn test_run_mcp_commands() {
+    let _config_lock = MCP_CONFIG_FILE_LOCK.lock().await;
     let app = mock_app();
+    app.manage(AppState::default());
 
     // Get the app path where the config should be created
     let app_path = get_jan_data_folder_path(app.handle().clone());
@@ -29,7 +61,7 @@ async fn test_run_mcp_commands() {
     // Call the run_mcp_commands function
     let servers_state: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>> =
         Arc::new(Mutex::new(HashMap::new()));
-    let result = run_mcp_commands(app.handle(), servers_state).await;
+    let result = run_mcp_commands(app.handle(), servers_state, false).await;
 
     // Assert that the function returns Ok(())
     assert!(result.is_ok());
@@ -37,3 +69,986 @@ async fn test_run_mcp_commands() {
     // Clean up the mock config file
     std::fs::remove_file(&config_path).expect("Failed to remove config file");
 }
+
+#[test]
+fn test_extract_max_restarts() {
+    let config = serde_json::json!({ "command": "npx", "args": [], "maxRestarts": 10 });
+    assert_eq!(extract_max_restarts(&config), Some(10));
+
+    let config = serde_json::json!({ "command": "npx", "args": [] });
+    assert_eq!(extract_max_restarts(&config), None);
+
+    let config = serde_json::json!({ "command": "npx", "args": [], "maxRestarts": "10" });
+    assert_eq!(extract_max_restarts(&config), None);
+}
+
+#[tokio::test]
+async fn test_max_restarts_zero_never_restarts() {
+    let app = mock_app();
+    let name = "flaky".to_string();
+    let config = serde_json::json!({ "command": "npx", "args": [], "maxRestarts": 0 });
+
+    let servers_state: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    let restart_counts = Arc::new(Mutex::new(HashMap::new()));
+    let successfully_connected = Arc::new(Mutex::new(HashMap::from([(name.clone(), true)])));
+
+    // The loop should give up before any backoff delay or restart attempt
+    timeout(
+        Duration::from_millis(500),
+        start_restart_loop(
+            app.handle().clone(),
+            servers_state.clone(),
+            name.clone(),
+            config.clone(),
+            extract_max_restarts(&config).unwrap(),
+            restart_counts.clone(),
+            successfully_connected,
+        ),
+    )
+    .await
+    .expect("restart loop should exit immediately");
+
+    assert!(servers_state.lock().await.is_empty());
+    assert_eq!(restart_counts.lock().await.get(&name), Some(&1));
+}
+
+#[test]
+fn test_extract_health_check_settings() {
+    let config = serde_json::json!({
+        "command": "npx",
+        "args": [],
+        "healthCheckIntervalMs": 30000,
+        "healthCheckTimeoutMs": 500
+    });
+    assert_eq!(
+        extract_health_check_settings(&config),
+        (Duration::from_secs(30), Duration::from_millis(500))
+    );
+
+    let config = serde_json::json!({ "command": "npx", "args": [] });
+    assert_eq!(
+        extract_health_check_settings(&config),
+        (MCP_HEALTH_CHECK_INTERVAL, MCP_HEALTH_CHECK_TIMEOUT)
+    );
+
+    let config = serde_json::json!({
+        "command": "npx",
+        "args": [],
+        "healthCheckIntervalMs": 0,
+        "healthCheckTimeoutMs": 0
+    });
+    assert_eq!(
+        extract_health_check_settings(&config),
+        (MCP_MIN_HEALTH_CHECK_INTERVAL, MCP_MIN_HEALTH_CHECK_TIMEOUT)
+    );
+
+    let config = serde_json::json!({
+        "command": "npx",
+        "args": [],
+        "healthCheckTimeoutMs": u64::MAX
+    });
+    assert_eq!(
+        extract_health_check_settings(&config),
+        (MCP_HEALTH_CHECK_INTERVAL, MCP_MAX_HEALTH_CHECK_TIMEOUT)
+    );
+}
+
+#[test]
+fn test_validate_mcp_server_config_health_check_limits() {
+    let config = serde_json::json!({
+        "command": "npx",
+        "args": [],
+        "healthCheckIntervalMs": 0,
+        "healthCheckTimeoutMs": 0
+    });
+    let fields: Vec<String> = validate_mcp_server_config("fetch", &config)
+        .into_iter()
+        .map(|e| e.field)
+        .collect();
+    assert_eq!(
+        fields,
+        vec!["healthCheckIntervalMs", "healthCheckTimeoutMs"]
+    );
+
+    let config = serde_json::json!({
+        "command": "npx",
+        "args": [],
+        "healthCheckIntervalMs": 1000,
+        "healthCheckTimeoutMs": 100
+    });
+    assert!(validate_mcp_server_config("fetch", &config).is_empty());
+
+    let config = serde_json::json!({
+        "command": "npx",
+        "args": [],
+        "healthCheckTimeoutMs": 60000
+    });
+    let errors = validate_mcp_server_config("fetch", &config);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "healthCheckTimeoutMs");
+    assert!(errors[0].message.contains("at most 30000"));
+}
+
+#[tokio::test]
+async fn test_is_saved_mcp_config() {
+    let path = std::env::temp_dir().join(format!(
+        "jan-saved-mcp-config-{}.json",
+        uuid::Uuid::new_v4()
+    ));
+    let saved_hash = Mutex::new(None);
+    std::fs::write(&path, r#"{"mcpServers":{}}"#).unwrap();
+    assert!(!is_saved_mcp_config(&saved_hash, &path).await);
+
+    // Jan's own save is recognized, an external edit afterwards is not
+    *saved_hash.lock().await = Some(hash_mcp_config(r#"{"mcpServers":{}}"#));
+    assert!(is_saved_mcp_config(&saved_hash, &path).await);
+    std::fs::write(&path, r#"{"mcpServers":{"fetch":{}}}"#).unwrap();
+    assert!(!is_saved_mcp_config(&saved_hash, &path).await);
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_diff_mcp_configs() {
+    let unchanged = serde_json::json!({ "command": "npx", "args": ["a"], "active": true });
+    let changed = serde_json::json!({ "command": "npx", "args": ["b"], "active": true });
+    let removed = serde_json::json!({ "command": "npx", "args": ["c"], "active": true });
+    let deactivated = serde_json::json!({ "command": "npx", "args": ["d"], "active": true });
+
+    let active_servers = HashMap::from([
+        ("unchanged".to_string(), unchanged.clone()),
+        ("changed".to_string(), changed),
+        ("removed".to_string(), removed),
+        ("deactivated".to_string(), deactivated),
+    ]);
+
+    let server_map = serde_json::json!({
+        "unchanged": unchanged,
+        "changed": { "command": "npx", "args": ["b", "--verbose"], "active": true },
+        "deactivated": { "command": "npx", "args": ["d"], "active": false },
+        "added": { "command": "uvx", "args": ["e"], "active": true },
+        "inactive": { "command": "uvx", "args": ["f"], "active": false }
+    });
+
+    let server_map = server_map.as_object().unwrap();
+    let diff = diff_mcp_configs(&active_servers, server_map, &HashSet::new());
+    assert_eq!(diff.started, vec!["added"]);
+    assert_eq!(diff.stopped, vec!["deactivated", "removed"]);
+    assert_eq!(diff.restarted, vec!["changed"]);
+
+    // a server stopped by hand isn't brought back by its config
+    let diff = diff_mcp_configs(
+        &active_servers,
+        server_map,
+        &HashSet::from(["added".to_string()]),
+    );
+    assert!(diff.started.is_empty());
+}
+
+#[test]
+fn test_mcp_server_health_payload() {
+    let payload = McpServerHealth {
+        name: "fetch".to_string(),
+        status: McpHealthStatus::Unreachable,
+        latency_ms: 2000,
+        timestamp: 1_700_000_000_000,
+    };
+    let value = serde_json::to_value(payload).unwrap();
+    assert_eq!(value["name"], "fetch");
+    assert_eq!(value["status"], "unreachable");
+    assert_eq!(value["latency_ms"], 2000);
+}
+
+#[test]
+fn test_expand_env_vars() {
+    std::env::set_var("API_TOKEN", "secret");
+    assert_eq!(
+        expand_env_vars("Bearer ${API_TOKEN}"),
+        Ok("Bearer secret".to_string())
+    );
+    assert_eq!(
+        expand_env_vars("no variables"),
+        Ok("no variables".to_string())
+    );
+    assert_eq!(
+        expand_env_vars("unterminated ${API_TOKEN"),
+        Ok("unterminated ${API_TOKEN".to_string())
+    );
+    assert_eq!(
+        expand_env_vars("${JAN_TEST_MISSING_VARIABLE}"),
+        Err("JAN_TEST_MISSING_VARIABLE".to_string())
+    );
+}
+
+#[test]
+fn test_resolve_working_dir() {
+    let home = dirs::home_dir().unwrap();
+    assert_eq!(resolve_working_dir("~"), Ok(home.clone()));
+    assert_eq!(
+        resolve_working_dir("~/mcp/servers"),
+        Ok(home.join("mcp/servers"))
+    );
+    // only a leading ~ followed by a separator is the home directory
+    assert_eq!(
+        resolve_working_dir("~user/servers"),
+        Ok(std::path::PathBuf::from("~user/servers"))
+    );
+
+    std::env::set_var("JAN_TEST_MCP_ROOT", "/opt/mcp");
+    assert_eq!(
+        resolve_working_dir("${JAN_TEST_MCP_ROOT}/fetch"),
+        Ok(std::path::PathBuf::from("/opt/mcp/fetch"))
+    );
+    assert_eq!(
+        resolve_working_dir("${JAN_TEST_MISSING_VARIABLE}/fetch"),
+        Err("JAN_TEST_MISSING_VARIABLE".to_string())
+    );
+}
+
+#[test]
+fn test_validate_mcp_server_config_valid() {
+    let config = serde_json::json!({
+        "command": "npx",
+        "args": ["-y", "@modelcontextprotocol/server-filesystem"],
+        "env": { "API_KEY": "${API_KEY}" },
+        "cwd": "~/mcp",
+        "active": true,
+        "maxRestarts": 2
+    });
+    assert!(validate_mcp_server_config("filesystem", &config).is_empty());
+}
+
+#[test]
+fn test_validate_mcp_server_config_missing_command() {
+    let config = serde_json::json!({ "args": [] });
+    let errors = validate_mcp_server_config("fetch", &config);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].server, "fetch");
+    assert_eq!(errors[0].field, "command");
+}
+
+#[test]
+fn test_validate_mcp_server_config_typo() {
+    let config = serde_json::json!({ "command": "uvx", "arg": ["mcp-server-fetch"] });
+    let errors = validate_mcp_server_config("fetch", &config);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "args");
+    assert!(errors[0].message.contains("did you mean \"args\""));
+}
+
+#[test]
+fn test_validate_mcp_server_config_wrong_types() {
+    let config = serde_json::json!({
+        "command": "",
+        "args": "mcp-server-fetch",
+        "env": { "PORT": 8080 },
+        "cwd": 42,
+        "active": "yes",
+        "maxRestarts": -1
+    });
+    let fields: Vec<String> = validate_mcp_server_config("fetch", &config)
+        .into_iter()
+        .map(|error| error.field)
+        .collect();
+    assert_eq!(
+        fields,
+        vec!["command", "args", "env", "cwd", "active", "maxRestarts"]
+    );
+
+    let errors = validate_mcp_server_config("fetch", &serde_json::json!(["npx"]));
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn test_sanitize_server_name() {
+    assert_eq!(
+        sanitize_server_name("sequential-thinking"),
+        "sequential-thinking"
+    );
+    assert_eq!(sanitize_server_name("my server/v2"), "my_server_v2");
+    assert_eq!(sanitize_server_name("../../etc"), ".._.._etc");
+    assert_eq!(sanitize_server_name(".."), "_..");
+}
+
+#[test]
+fn test_truncate_output_line() {
+    assert_eq!(
+        truncate_output_line("listening on stdio\r\n", 100),
+        "listening on stdio"
+    );
+    assert_eq!(truncate_output_line("", 100), "");
+    assert_eq!(truncate_output_line("abcdef\n", 3), "abc…");
+    // cut on a char boundary
+    assert_eq!(truncate_output_line("ééé", 2), "éé…");
+    assert_eq!(truncate_output_line("ééé", 3), "ééé");
+}
+
+#[test]
+fn test_mcp_output_rate_limit() {
+    let start = Instant::now();
+    let mut limit = McpOutputRateLimit::new(2, start);
+    assert_eq!(limit.admit(start), (true, 0));
+    assert_eq!(limit.admit(start + Duration::from_millis(100)), (true, 0));
+    assert_eq!(limit.admit(start + Duration::from_millis(200)), (false, 0));
+    assert_eq!(limit.admit(start + Duration::from_millis(300)), (false, 0));
+
+    // the next window reports what the previous one dropped, once
+    let next = start + Duration::from_millis(1000);
+    assert_eq!(limit.admit(next), (true, 2));
+    assert_eq!(limit.admit(next + Duration::from_millis(1)), (true, 0));
+    assert_eq!(limit.admit(next + Duration::from_millis(2)), (false, 0));
+}
+
+#[test]
+fn test_read_mcp_server_log_tail() {
+    let app_path = std::env::temp_dir().join(format!("jan-mcp-log-{}", std::process::id()));
+    assert!(read_mcp_server_log_tail(&app_path, "fetch", 10)
+        .unwrap()
+        .is_empty());
+
+    let log_dir = app_path.join("logs").join("mcp");
+    std::fs::create_dir_all(&log_dir).unwrap();
+    // long enough to span several chunks
+    let content: String = (0..5000).map(|i| format!("line {i}\n")).collect();
+    std::fs::write(log_dir.join("my_server.log"), content).unwrap();
+
+    let tail = read_mcp_server_log_tail(&app_path, "my server", 3).unwrap();
+    assert_eq!(tail, vec!["line 4997", "line 4998", "line 4999"]);
+    let all = read_mcp_server_log_tail(&app_path, "my server", 10_000).unwrap();
+    assert_eq!(all.len(), 5000);
+    assert_eq!(all[0], "line 0");
+    // names are sanitized like the log writer does, so this can't escape logs/mcp
+    assert!(read_mcp_server_log_tail(&app_path, "../mcp/my server", 3)
+        .unwrap()
+        .is_empty());
+
+    let _ = std::fs::remove_dir_all(app_path);
+}
+
+#[test]
+fn test_mcp_error_display() {
+    let error = McpError::MissingEnvVar {
+        server: "serper".to_string(),
+        key: "SERPER_API_KEY".to_string(),
+        var: "SERPER_TOKEN".to_string(),
+    };
+    assert_eq!(
+        error.to_string(),
+        "Environment variable SERPER_TOKEN used in SERPER_API_KEY for MCP server serper is not set"
+    );
+    assert_eq!(
+        McpError::ServerNotFound("fetch".to_string()).to_string(),
+        "Server fetch not found"
+    );
+    let error = McpError::VerificationTimeout {
+        server: "fetch".to_string(),
+        timeout_ms: 5000,
+    };
+    assert_eq!(
+        error.to_string(),
+        "MCP server fetch did not answer within 5000ms after starting"
+    );
+}
+
+#[tokio::test]
+async fn test_join_with_concurrency_limit() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let running = Arc::new(AtomicUsize::new(0));
+    let max_running = Arc::new(AtomicUsize::new(0));
+
+    let tasks: Vec<_> = (0..10)
+        .map(|i| {
+            let running = running.clone();
+            let max_running = max_running.clone();
+            async move {
+                let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now_running, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                i
+            }
+        })
+        .collect();
+
+    let results = join_with_concurrency_limit(tasks, 4).await;
+
+    assert!(max_running.load(Ordering::SeqCst) <= 4);
+    let results: Vec<i32> = results.into_iter().map(Result::unwrap).collect();
+    assert_eq!(results, (0..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_extract_restart_count_reset_window() {
+    let config = serde_json::json!({ "command": "npx", "args": [], "restartCountResetMs": 60000 });
+    assert_eq!(
+        extract_restart_count_reset_window(&config),
+        Duration::from_secs(60)
+    );
+
+    let config = serde_json::json!({ "command": "npx", "args": [] });
+    assert_eq!(
+        extract_restart_count_reset_window(&config),
+        MCP_RESTART_COUNT_RESET_WINDOW
+    );
+}
+
+#[test]
+fn test_extract_tool_call_timeout() {
+    let config = serde_json::json!({ "command": "npx", "args": [], "toolCallTimeoutMs": 120000 });
+    assert_eq!(extract_tool_call_timeout(&config), Duration::from_secs(120));
+
+    let config = serde_json::json!({ "command": "npx", "args": [] });
+    assert_eq!(extract_tool_call_timeout(&config), MCP_TOOL_CALL_TIMEOUT);
+
+    let config = serde_json::json!({ "command": "npx", "args": [], "toolCallTimeoutMs": u64::MAX });
+    assert_eq!(
+        extract_tool_call_timeout(&config),
+        MCP_MAX_TOOL_CALL_TIMEOUT
+    );
+}
+
+#[test]
+fn test_extract_startup_verify_timeout() {
+    let config =
+        serde_json::json!({ "command": "uvx", "args": [], "startupVerifyTimeoutMs": 20000 });
+    assert_eq!(
+        extract_startup_verify_timeout(&config),
+        Duration::from_secs(20)
+    );
+
+    let config = serde_json::json!({ "command": "uvx", "args": [] });
+    assert_eq!(
+        extract_startup_verify_timeout(&config),
+        MCP_STARTUP_VERIFY_TIMEOUT
+    );
+}
+
+#[tokio::test]
+async fn test_verify_mcp_server_started_quit() {
+    let servers: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    let result = verify_mcp_server_started(&servers, "fetch", Duration::from_secs(5)).await;
+    assert!(matches!(result, Err(McpError::QuitImmediately(name)) if name == "fetch"));
+}
+
+#[test]
+fn test_tool_call_timeout_error_display() {
+    let error = McpError::ToolCallTimeout {
+        server: "fetch".to_string(),
+        tool: "fetch_url".to_string(),
+        timeout_ms: 30000,
+    };
+    assert_eq!(
+        error.to_string(),
+        "Tool call 'fetch_url' on MCP server fetch timed out after 30000ms"
+    );
+}
+
+#[test]
+fn test_exponential_backoff_delay_bounds() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for attempt in 0..=20 {
+        for _ in 0..100 {
+            let delay = calculate_exponential_backoff_delay_with_rng(attempt, &mut rng);
+            assert!(
+                delay >= 100,
+                "attempt {attempt}: {delay}ms is below the minimum"
+            );
+            assert!(
+                delay <= MCP_MAX_RESTART_DELAY_MS,
+                "attempt {attempt}: {delay}ms is above the maximum"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_exponential_backoff_delay_jitter() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let delays: Vec<u64> = (0..100)
+        .map(|_| calculate_exponential_backoff_delay_with_rng(1, &mut rng))
+        .collect();
+
+    // First attempt stays within ±25% of the base delay, and isn't the same every time
+    let jitter = MCP_BASE_RESTART_DELAY_MS / 4;
+    assert!(delays.iter().all(|d| (MCP_BASE_RESTART_DELAY_MS - jitter
+        ..=MCP_BASE_RESTART_DELAY_MS + jitter)
+        .contains(d)));
+    assert!(delays.iter().any(|d| *d != delays[0]));
+}
+
+#[tokio::test]
+async fn test_get_mcp_server_statuses() {
+    let state = AppState::default();
+    state
+        .mcp_active_servers
+        .lock()
+        .await
+        .insert("fetch".to_string(), serde_json::json!({"command": "uvx"}));
+    state
+        .mcp_restart_counts
+        .lock()
+        .await
+        .insert("fetch".to_string(), 2);
+    // deactivated server that had connected before
+    state
+        .mcp_successfully_connected
+        .lock()
+        .await
+        .insert("browser".to_string(), false);
+
+    let statuses = get_mcp_server_statuses(&state).await;
+    let names: Vec<&str> = statuses.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["browser", "fetch"]);
+
+    assert_eq!(statuses[0].state, McpServerState::Stopped);
+    assert_eq!(statuses[0].restart_count, 0);
+
+    // active in config but not in the running map
+    assert_eq!(statuses[1].state, McpServerState::Connecting);
+    assert_eq!(statuses[1].restart_count, 2);
+    assert!(!statuses[1].successfully_connected);
+    assert!(statuses[1].peer_info.is_none());
+}
+
+#[tokio::test]
+async fn test_drain_mcp_server_not_running() {
+    let state = AppState::default();
+    state
+        .mcp_active_servers
+        .lock()
+        .await
+        .insert("fetch".to_string(), serde_json::json!({"command": "uvx"}));
+    let fetch_call = register_mcp_tool_call(&state.mcp_tool_calls, "fetch", "req-1")
+        .await
+        .unwrap();
+    let time_call = register_mcp_tool_call(&state.mcp_tool_calls, "time", "req-2")
+        .await
+        .unwrap();
+
+    assert!(!drain_mcp_server(&state, "fetch").await.unwrap());
+
+    // no longer considered for restarts, and its calls were cancelled
+    assert!(state.mcp_stopped_servers.lock().await.contains("fetch"));
+    assert!(!state.mcp_active_servers.lock().await.contains_key("fetch"));
+    assert!(fetch_call.is_cancelled());
+    assert!(!time_call.is_cancelled());
+}
+
+#[tokio::test]
+async fn test_clean_up_mcp_servers_aborts_monitors() {
+    let app = mock_app();
+    app.manage(AppState::default());
+    let state = app.state::<AppState>();
+
+    let monitor = tauri::async_runtime::spawn(std::future::pending::<()>());
+    let monitor_abort = monitor.inner().abort_handle();
+    state
+        .mcp_monitor_tasks
+        .lock()
+        .await
+        .insert("fetch".to_string(), monitor);
+
+    clean_up_mcp_servers(app.state::<AppState>()).await;
+    assert!(state.mcp_monitor_tasks.lock().await.is_empty());
+    timeout(Duration::from_secs(5), async {
+        while !monitor_abort.is_finished() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("monitor should be aborted");
+}
+
+#[tokio::test]
+async fn test_drained_mcp_server_stays_down_on_config_reload() {
+    let _config_lock = MCP_CONFIG_FILE_LOCK.lock().await;
+    let app = mock_app();
+    app.manage(AppState::default());
+    let state = app.state::<AppState>();
+
+    let config =
+        serde_json::json!({ "command": "jan-missing-mcp-server", "args": [], "active": true });
+    state
+        .mcp_active_servers
+        .lock()
+        .await
+        .insert("fetch".to_string(), config.clone());
+    assert!(!drain_mcp_server(&state, "fetch").await.unwrap());
+
+    // the frontend saves the config again, still listing the server as active
+    let app_path = get_jan_data_folder_path(app.handle().clone());
+    std::fs::create_dir_all(&app_path).unwrap();
+    let config_path = app_path.join("mcp_config.json");
+    std::fs::write(
+        &config_path,
+        serde_json::json!({ "mcpServers": { "fetch": config } }).to_string(),
+    )
+    .unwrap();
+
+    let diff = reload_mcp_config(app.handle()).await.unwrap();
+    assert!(diff.is_empty());
+    assert!(!state.mcp_active_servers.lock().await.contains_key("fetch"));
+
+    std::fs::remove_file(&config_path).unwrap();
+}
+
+#[test]
+fn test_circuit_breaker() {
+    let start = std::time::Instant::now();
+    let mut breaker = McpCircuitBreaker::default();
+
+    // failures spread wider than the window never open the breaker
+    for i in 0..MCP_CIRCUIT_BREAKER_THRESHOLD as u32 * 2 {
+        let now = start + (MCP_CIRCUIT_BREAKER_WINDOW + Duration::from_secs(1)) * i;
+        assert!(!breaker.record_failure(now));
+    }
+
+    // rapid failures open it at the threshold
+    let later = start + MCP_CIRCUIT_BREAKER_WINDOW * 100;
+    for i in 1..MCP_CIRCUIT_BREAKER_THRESHOLD {
+        assert!(!breaker.record_failure(later + Duration::from_secs(i as u64)));
+    }
+    assert!(breaker.record_failure(later + Duration::from_secs(10)));
+    assert!(breaker.is_half_open());
+
+    // a failed probe reopens it immediately
+    assert!(breaker.record_failure(later + Duration::from_secs(20)));
+
+    // a successful probe closes it
+    breaker.record_success();
+    assert!(!breaker.is_half_open());
+    assert!(!breaker.record_failure(later + Duration::from_secs(30)));
+}
+
+#[tokio::test]
+async fn test_list_all_mcp_tools_without_servers() {
+    let servers: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    let catalog = list_all_mcp_tools(&servers).await;
+    assert!(catalog.tools.is_empty());
+    assert!(catalog.servers.is_empty());
+}
+
+#[tokio::test]
+async fn test_get_mcp_tool_schemas_from_cache() {
+    let schema = serde_json::json!({
+        "type": "object",
+        "properties": { "url": { "type": "string" } }
+    });
+    let tool = rmcp::model::Tool::new(
+        "fetch",
+        "Fetches a URL",
+        Arc::new(schema.as_object().unwrap().clone()),
+    );
+    let servers: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    let tool_cache = Arc::new(Mutex::new(HashMap::from([(
+        "fetch".to_string(),
+        vec![tool],
+    )])));
+
+    let schemas = get_mcp_tool_schemas(&servers, &tool_cache, "fetch")
+        .await
+        .unwrap();
+    assert_eq!(schemas.get("fetch"), Some(&schema));
+
+    // Not cached and not running
+    let err = get_mcp_tool_schemas(&servers, &tool_cache, "serper")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, McpError::ServerNotFound(name) if name == "serper"));
+}
+
+#[tokio::test]
+async fn test_pause_mcp_monitoring() {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use tauri::Listener;
+
+    let app = mock_app();
+    let emitted = Arc::new(AtomicUsize::new(0));
+    let emitted_clone = emitted.clone();
+    app.listen("mcp_monitoring_paused", move |_| {
+        emitted_clone.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let paused = Arc::new(AtomicBool::new(false));
+    assert!(set_mcp_monitoring_paused(app.handle(), &paused, true));
+    // Pausing twice doesn't emit again
+    assert!(!set_mcp_monitoring_paused(app.handle(), &paused, true));
+    assert_eq!(emitted.load(Ordering::SeqCst), 1);
+
+    // A restart waiting on the pause continues once monitoring resumes
+    let paused_clone = paused.clone();
+    let waiter = tokio::spawn(async move {
+        wait_while_mcp_monitoring_paused(&paused_clone, "fetch").await;
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!waiter.is_finished());
+    assert!(set_mcp_monitoring_paused(app.handle(), &paused, false));
+    timeout(Duration::from_secs(5), waiter)
+        .await
+        .expect("restart should resume")
+        .unwrap();
+    assert_eq!(emitted.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_restart_mcp_servers_and_wait_without_servers() {
+    use tauri::Listener;
+
+    let app = mock_app();
+    let completed = Arc::new(std::sync::Mutex::new(None));
+    let completed_clone = completed.clone();
+    app.listen("mcp_restart_complete", move |event| {
+        *completed_clone.lock().unwrap() = Some(event.payload().to_string());
+    });
+
+    let servers: Arc<Mutex<HashMap<String, RunningService<RoleClient, ()>>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    let summary = restart_mcp_servers_and_wait(app.handle(), servers, HashMap::new()).await;
+    assert_eq!(summary, McpRestartSummary::default());
+    assert_eq!(
+        completed.lock().unwrap().as_deref(),
+        Some(r#"{"successful":0,"failed":0}"#)
+    );
+}
+
+#[tokio::test]
+async fn test_mcp_event_log() {
+    let events = Mutex::new(std::collections::VecDeque::new());
+    record_mcp_event(
+        &events,
+        "fetch",
+        McpLifecycleEventKind::Start,
+        "Starting server",
+    )
+    .await;
+    record_mcp_event(
+        &events,
+        "browser",
+        McpLifecycleEventKind::Start,
+        "Starting server",
+    )
+    .await;
+    record_mcp_event(
+        &events,
+        "fetch",
+        McpLifecycleEventKind::Connected,
+        "Connected",
+    )
+    .await;
+    record_mcp_event(
+        &events,
+        "fetch",
+        McpLifecycleEventKind::HealthFail,
+        "Unreachable",
+    )
+    .await;
+
+    // latest events of one server, oldest first
+    let fetch_events = query_mcp_events(&events, Some("fetch"), 2).await;
+    let kinds: Vec<_> = fetch_events.iter().map(|e| e.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            McpLifecycleEventKind::Connected,
+            McpLifecycleEventKind::HealthFail
+        ]
+    );
+    assert_eq!(query_mcp_events(&events, None, 10).await.len(), 4);
+
+    // the log is capped, dropping the oldest events
+    for _ in 0..MCP_EVENT_LOG_CAPACITY {
+        record_mcp_event(
+            &events,
+            "browser",
+            McpLifecycleEventKind::Restart,
+            "Restart",
+        )
+        .await;
+    }
+    assert_eq!(events.lock().await.len(), MCP_EVENT_LOG_CAPACITY);
+    assert!(query_mcp_events(&events, Some("fetch"), 10)
+        .await
+        .is_empty());
+}
+
+#[test]
+fn test_sample_mcp_process_stats() {
+    let server_pids = HashMap::from([
+        ("self".to_string(), std::process::id()),
+        ("exited".to_string(), u32::MAX - 1),
+    ]);
+    let stats = sample_mcp_process_stats(&server_pids);
+
+    let own = stats["self"].as_ref().unwrap();
+    assert_eq!(own.pid, std::process::id());
+    assert!(own.memory_bytes > 0);
+    assert!(stats["exited"].is_none());
+}
+
+#[tokio::test]
+async fn test_test_mcp_server_config_errors() {
+    let app = mock_app();
+
+    // invalid configs are rejected before spawning anything
+    let error = test_mcp_server_config(app.handle(), &serde_json::json!({ "args": [] }))
+        .await
+        .unwrap_err();
+    assert!(error.contains("\"command\""), "{error}");
+
+    let config = serde_json::json!({
+        "command": "jan-test-missing-mcp-server-binary",
+        "args": []
+    });
+    let error = test_mcp_server_config(app.handle(), &config)
+        .await
+        .unwrap_err();
+    assert!(error.starts_with("Failed to run command"), "{error}");
+}
+
+#[tokio::test]
+async fn test_cancel_mcp_tool_call() {
+    let tool_calls = Mutex::new(HashMap::new());
+
+    let cancel_token = register_mcp_tool_call(&tool_calls, "fetch", "call-1")
+        .await
+        .unwrap();
+    assert!(matches!(
+        register_mcp_tool_call(&tool_calls, "fetch", "call-1").await,
+        Err(McpError::ToolCallInFlight { .. })
+    ));
+    // The same request id on another server is a different call
+    let other_token = register_mcp_tool_call(&tool_calls, "search", "call-1")
+        .await
+        .unwrap();
+
+    cancel_mcp_tool_call(&tool_calls, "fetch", "call-1")
+        .await
+        .unwrap();
+    assert!(cancel_token.is_cancelled());
+    assert!(!other_token.is_cancelled());
+
+    finish_mcp_tool_call(&tool_calls, "fetch", "call-1").await;
+    assert!(matches!(
+        cancel_mcp_tool_call(&tool_calls, "fetch", "call-1").await,
+        Err(McpError::ToolCallNotFound { .. })
+    ));
+    assert_eq!(tool_calls.lock().await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_call_tool_on_unknown_server() {
+    let app = mock_app();
+    app.manage(AppState::default());
+
+    let error = call_tool(
+        app.state::<AppState>(),
+        "fetch".to_string(),
+        None,
+        Some("call-1".to_string()),
+        Some("missing".to_string()),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        McpError::ServerNotFound("missing".to_string()).to_string()
+    );
+    assert!(app
+        .state::<AppState>()
+        .mcp_tool_calls
+        .lock()
+        .await
+        .is_empty());
+}
+
+#[test]
+fn test_extract_init_payload() {
+    let config = serde_json::json!({ "init": { "token": "abc" } });
+    assert_eq!(
+        extract_init_payload(&config).unwrap(),
+        b"{\"token\":\"abc\"}\n".to_vec()
+    );
+    let config = serde_json::json!({ "init": "hello\n" });
+    assert_eq!(extract_init_payload(&config).unwrap(), b"hello\n".to_vec());
+    assert!(extract_init_payload(&serde_json::json!({})).is_none());
+
+    let config = serde_json::json!({ "command": "npx", "args": [], "init": 42 });
+    let errors = validate_mcp_server_config("boot", &config);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "init");
+}
+
+#[tokio::test]
+async fn test_write_mcp_init_payload() {
+    use tokio::io::AsyncReadExt;
+
+    let (mut stdin, mut server) = tokio::io::duplex(64);
+    write_mcp_init_payload(&mut stdin, "boot", b"hello\n", Duration::from_secs(1))
+        .await
+        .unwrap();
+    let mut received = [0u8; 6];
+    server.read_exact(&mut received).await.unwrap();
+    assert_eq!(&received, b"hello\n");
+
+    // The server doesn't read its stdin
+    let (mut stdin, _server) = tokio::io::duplex(4);
+    let result =
+        write_mcp_init_payload(&mut stdin, "boot", b"hello\n", Duration::from_millis(50)).await;
+    assert!(matches!(result, Err(McpError::StartFailed { .. })));
+
+    // The server closed its stdin
+    let (mut stdin, server) = tokio::io::duplex(64);
+    drop(server);
+    let result =
+        write_mcp_init_payload(&mut stdin, "boot", b"hello\n", Duration::from_secs(1)).await;
+    assert!(
+        matches!(result, Err(McpError::StartFailed { reason, .. }) if reason.contains("closed stdin"))
+    );
+}
+
+#[test]
+fn test_select_mcp_servers_to_start() {
+    let server_map = serde_json::json!({
+        "fetch": { "command": "uvx", "args": ["mcp-server-fetch"] },
+        "time": { "command": "uvx", "args": ["mcp-server-time"], "active": true },
+        "disabled": { "command": "uvx", "args": [], "active": false }
+    });
+    let server_map = server_map.as_object().unwrap();
+
+    // First run starts every active server
+    let mut running = HashSet::new();
+    let mut stopped = HashSet::new();
+    let started = select_mcp_servers_to_start(server_map, &running, &stopped, false);
+    assert_eq!(started, vec!["fetch", "time"]);
+    running.extend(started);
+
+    // Running again leaves exactly one instance per server
+    assert!(select_mcp_servers_to_start(server_map, &running, &stopped, false).is_empty());
+
+    // Only the server that went away is started again
+    running.remove("time");
+    assert_eq!(
+        select_mcp_servers_to_start(server_map, &running, &stopped, false),
+        vec!["time"]
+    );
+
+    // Forcing restarts the running ones too
+    running.insert("time".to_string());
+    assert_eq!(
+        select_mcp_servers_to_start(server_map, &running, &stopped, true),
+        vec!["fetch", "time"]
+    );
+
+    // Servers stopped by hand stay stopped, unless their config says they're active
+    running.clear();
+    stopped.extend(["fetch".to_string(), "time".to_string()]);
+    assert_eq!(
+        select_mcp_servers_to_start(server_map, &running, &stopped, false),
+        vec!["time"]
+    );
+}
@@ -13,8 +13,10 @@ use tauri_plugin_store::StoreExt;
 
 // MCP
 use super::{
-    app::commands::get_jan_data_folder_path, extensions::commands::get_jan_extensions_path,
-    mcp::helpers::run_mcp_commands, state::AppState,
+    app::commands::get_jan_data_folder_path,
+    extensions::commands::get_jan_extensions_path,
+    mcp::helpers::{run_mcp_commands, watch_mcp_config},
+    state::AppState,
 };
 
 pub fn install_extensions(app: tauri::AppHandle, force: bool) -> Result<(), String> {
@@ -199,9 +201,12 @@ pub fn setup_mcp(app: &App) {
     let servers = state.mcp_servers.clone();
     let app_handle: tauri::AppHandle = app.handle().clone();
     tauri::async_runtime::spawn(async move {
-        if let Err(e) = run_mcp_commands(&app_handle, servers).await {
+        if let Err(e) = run_mcp_commands(&app_handle, servers, false).await {
             log::error!("Failed to run mcp commands: {}", e);
         }
+        if let Err(e) = watch_mcp_config(app_handle.clone()) {
+            log::error!("Failed to watch mcp config: {}", e);
+        }
         app_handle
             .emit("mcp-update", "MCP servers updated")
             .unwrap();
@@ -1,8 +1,12 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{atomic::AtomicBool, Arc},
+};
 
-use crate::core::downloads::models::DownloadManagerState;
-use rmcp::{service::RunningService, RoleClient};
+use crate::core::{downloads::models::DownloadManagerState, mcp::models::McpLifecycleEvent};
+use rmcp::{model::Tool, service::RunningService, RoleClient};
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
 /// Server handle type for managing the proxy server lifecycle
 pub type ServerHandle = JoinHandle<Result<(), Box<dyn std::error::Error + Send + Sync>>>;
@@ -16,5 +20,16 @@ pub struct AppState {
     pub mcp_restart_counts: Arc<Mutex<HashMap<String, u32>>>,
     pub mcp_active_servers: Arc<Mutex<HashMap<String, serde_json::Value>>>,
     pub mcp_successfully_connected: Arc<Mutex<HashMap<String, bool>>>,
+    pub mcp_server_pids: Arc<Mutex<HashMap<String, u32>>>,
+    pub mcp_tool_cache: Arc<Mutex<HashMap<String, Vec<Tool>>>>,
+    pub mcp_monitor_tasks: Arc<Mutex<HashMap<String, tauri::async_runtime::JoinHandle<()>>>>,
+    pub mcp_events: Arc<Mutex<VecDeque<McpLifecycleEvent>>>,
+    pub mcp_monitoring_paused: Arc<AtomicBool>,
+    pub mcp_tool_calls: Arc<Mutex<HashMap<(String, String), CancellationToken>>>,
+    /// Servers stopped with the `stop_mcp_server` command, kept down until started again
+    pub mcp_stopped_servers: Arc<Mutex<HashSet<String>>>,
+    /// Hash of the mcp_config.json last written by `save_mcp_configs`, so the config
+    /// watcher can ignore Jan's own saves
+    pub mcp_saved_config_hash: Arc<Mutex<Option<u64>>>,
     pub server_handle: Arc<Mutex<Option<ServerHandle>>>,
 }
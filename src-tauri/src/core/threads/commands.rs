@@ -1,46 +1,62 @@
-use std::fs::{self, File};
-use std::io::Write;
+use std::fs;
 use tauri::Runtime;
 use uuid::Uuid;
 
 use super::helpers::{
-    get_lock_for_thread, read_messages_from_file, update_thread_metadata, write_messages_to_file,
+    append_message_to_file, edit_thread_metadata, lock_thread, read_messages_before,
+    read_messages_lenient, read_messages_paginated, read_thread_index, read_thread_metadata,
+    remove_from_thread_index, thread_metadata_exists, update_thread_metadata,
 };
 use super::{
-    constants::THREADS_FILE,
-    utils::{
-        ensure_data_dirs, ensure_thread_dir_exists, get_data_dir, get_messages_path,
-        get_thread_dir, get_thread_metadata_path,
+    constants::THREAD_LOCK_TIMEOUT,
+    helpers,
+    models::{
+        MessageCursorPage, MessagePage, MessageSearchResult, ThreadIndexEntry, ThreadPreview,
+        ThreadVerificationReport,
     },
+    utils::{ensure_data_dirs, get_thread_dir, get_thread_index_path, get_thread_metadata_path},
 };
 
-/// Lists all threads by reading their metadata from the threads directory.
+/// Lists all threads by reading the metadata of every thread in the thread index.
+/// The index is rebuilt from the threads directory if it is missing or unreadable.
 /// Returns a vector of thread metadata as JSON values.
 #[tauri::command]
 pub async fn list_threads<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
 ) -> Result<Vec<serde_json::Value>, String> {
     ensure_data_dirs(app_handle.clone())?;
-    let data_dir = get_data_dir(app_handle.clone());
     let mut threads = Vec::new();
 
-    if !data_dir.exists() {
-        return Ok(threads);
-    }
+    // The index lists the threads without scanning the data folder, it is only rebuilt
+    // when it is missing or unreadable
+    let index = if get_thread_index_path(app_handle.clone()).exists() {
+        read_thread_index(app_handle.clone())
+            .map_err(|e| log::warn!("Rebuilding unreadable thread index: {}", e))
+            .ok()
+    } else {
+        None
+    };
+    let thread_ids: Vec<String> = match index {
+        Some(index) => index.into_keys().collect(),
+        None => {
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn_blocking(move || helpers::rebuild_thread_index(app_handle))
+                .await
+                .map_err(|e| e.to_string())??
+                .into_iter()
+                .map(|entry| entry.id)
+                .collect()
+        }
+    };
 
-    for entry in fs::read_dir(&data_dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
-        if path.is_dir() {
-            let thread_metadata_path = path.join(THREADS_FILE);
-            if thread_metadata_path.exists() {
-                let data = fs::read_to_string(&thread_metadata_path).map_err(|e| e.to_string())?;
-                match serde_json::from_str(&data) {
-                    Ok(thread) => threads.push(thread),
-                    Err(e) => {
-                        println!("Failed to parse thread file: {}", e);
-                        continue; // skip invalid thread files
-                    }
+    for thread_id in thread_ids {
+        let thread_metadata_path = get_thread_metadata_path(app_handle.clone(), &thread_id);
+        if thread_metadata_exists(&thread_metadata_path) {
+            match read_thread_metadata(&thread_metadata_path) {
+                Ok(thread) => threads.push(thread),
+                Err(e) => {
+                    println!("Failed to parse thread file: {}", e);
+                    continue; // skip invalid thread files
                 }
             }
         }
@@ -49,6 +65,16 @@ pub async fn list_threads<R: Runtime>(
     Ok(threads)
 }
 
+/// Lists all threads with their title, update time and a preview of the last message,
+/// most recently updated first. Only the end of each thread's messages file is read,
+/// so this stays fast with hundreds of threads. Unreadable threads are skipped.
+#[tauri::command]
+pub async fn list_threads_with_preview<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+) -> Result<Vec<ThreadPreview>, String> {
+    helpers::list_threads_with_preview(app_handle).await
+}
+
 /// Creates a new thread, assigns it a unique ID, and persists its metadata.
 /// Ensures the thread directory exists and writes thread.json.
 #[tauri::command]
@@ -63,13 +89,12 @@ pub async fn create_thread<R: Runtime>(
     if !thread_dir.exists() {
         fs::create_dir_all(&thread_dir).map_err(|e| e.to_string())?;
     }
-    let path = get_thread_metadata_path(app_handle.clone(), &uuid);
-    let data = serde_json::to_string_pretty(&thread).map_err(|e| e.to_string())?;
-    fs::write(path, data).map_err(|e| e.to_string())?;
+    update_thread_metadata(app_handle, &uuid, &thread).await?;
     Ok(thread)
 }
 
 /// Modifies an existing thread's metadata by overwriting its thread.json file.
+/// The previous metadata is kept as a backup.
 /// Returns an error if the thread directory does not exist.
 #[tauri::command]
 pub async fn modify_thread<R: Runtime>(
@@ -84,10 +109,27 @@ pub async fn modify_thread<R: Runtime>(
     if !thread_dir.exists() {
         return Err("Thread directory does not exist".to_string());
     }
-    let path = get_thread_metadata_path(app_handle.clone(), thread_id);
-    let data = serde_json::to_string_pretty(&thread).map_err(|e| e.to_string())?;
-    fs::write(path, data).map_err(|e| e.to_string())?;
-    Ok(())
+    update_thread_metadata(app_handle, thread_id, &thread).await
+}
+
+/// Regenerates the thread index from the thread folders, for recovery.
+/// Returns the id, title, update time and message count of every thread.
+#[tauri::command]
+pub async fn rebuild_thread_index<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+) -> Result<Vec<ThreadIndexEntry>, String> {
+    tauri::async_runtime::spawn_blocking(move || helpers::rebuild_thread_index(app_handle))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Restores a thread's metadata from the backup taken before its last update.
+#[tauri::command]
+pub async fn restore_thread_metadata<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+) -> Result<(), String> {
+    helpers::restore_thread_metadata(app_handle, &thread_id).await
 }
 
 /// Deletes a thread and all its associated files by removing its directory.
@@ -100,17 +142,77 @@ pub async fn delete_thread<R: Runtime>(
     if thread_dir.exists() {
         let _ = fs::remove_dir_all(thread_dir);
     }
-    Ok(())
+    remove_from_thread_index(app_handle, &thread_id)
 }
 
 /// Lists all messages for a given thread by reading and parsing its messages.jsonl file.
-/// Returns a vector of message JSON values.
+/// Malformed lines are skipped. Returns a vector of message JSON values.
 #[tauri::command]
 pub async fn list_messages<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
     thread_id: String,
 ) -> Result<Vec<serde_json::Value>, String> {
-    read_messages_from_file(app_handle, &thread_id)
+    let _guard = lock_thread(&thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+    read_messages_lenient(app_handle, &thread_id)
+}
+
+/// Lists a page of messages for a given thread, counting `offset` from the newest message.
+/// Returns the messages in chronological order along with the thread's message count.
+#[tauri::command]
+pub async fn list_messages_paginated<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+    offset: usize,
+    limit: usize,
+) -> Result<MessagePage, String> {
+    let _guard = lock_thread(&thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+    read_messages_paginated(app_handle, &thread_id, offset, limit)
+}
+
+/// Lists up to `limit` messages older than `before_message_id`, newest first, for
+/// loading history as the user scrolls up. Pass `None` to start from the newest message,
+/// then the returned `next_cursor` until it is `None`.
+#[tauri::command]
+pub async fn list_messages_before<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+    before_message_id: Option<String>,
+    limit: usize,
+) -> Result<MessageCursorPage, String> {
+    let _guard = lock_thread(&thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+    read_messages_before(app_handle, &thread_id, before_message_id.as_deref(), limit)
+}
+
+/// Searches a thread's messages for the given text, ignoring case.
+/// Returns the id of each matching message with a snippet around the first match.
+#[tauri::command]
+pub async fn search_messages<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+    query: String,
+) -> Result<Vec<MessageSearchResult>, String> {
+    let _guard = lock_thread(&thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+    helpers::search_messages(app_handle, &thread_id, &query)
+}
+
+/// Exports a thread as a Markdown transcript for the frontend to save or share.
+#[tauri::command]
+pub async fn export_thread_markdown<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+) -> Result<String, String> {
+    let _guard = lock_thread(&thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+    helpers::export_thread_markdown(app_handle, &thread_id)
 }
 
 /// Appends a new message to a thread's messages.jsonl file.
@@ -127,28 +229,12 @@ pub async fn create_message<R: Runtime>(
             .ok_or("Missing thread_id")?;
         id.to_string()
     };
-    ensure_thread_dir_exists(app_handle.clone(), &thread_id)?;
-    let path = get_messages_path(app_handle.clone(), &thread_id);
-
     if message.get("id").is_none() {
         let uuid = Uuid::new_v4().to_string();
         message["id"] = serde_json::Value::String(uuid);
     }
 
-    // Acquire per-thread lock before writing
-    {
-        let lock = get_lock_for_thread(&thread_id).await;
-        let _guard = lock.lock().await;
-
-        let mut file: File = fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(path)
-            .map_err(|e| e.to_string())?;
-
-        let data = serde_json::to_string(&message).map_err(|e| e.to_string())?;
-        writeln!(file, "{}", data).map_err(|e| e.to_string())?;
-    }
+    append_message_to_file(app_handle, &thread_id, &message).await?;
 
     Ok(message)
 }
@@ -156,6 +242,7 @@ pub async fn create_message<R: Runtime>(
 /// Modifies an existing message in a thread's messages.jsonl file.
 /// Uses a per-thread async lock to prevent race conditions and ensure file consistency.
 /// Rewrites the entire messages.jsonl file for the thread.
+/// Returns an error if the message doesn't exist.
 #[tauri::command]
 pub async fn modify_message<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
@@ -170,49 +257,62 @@ pub async fn modify_message<R: Runtime>(
         .and_then(|v| v.as_str())
         .ok_or("Missing message id")?;
 
-    // Acquire per-thread lock before modifying
-    {
-        let lock = get_lock_for_thread(thread_id).await;
-        let _guard = lock.lock().await;
-
-        let mut messages = read_messages_from_file(app_handle.clone(), thread_id)?;
-        if let Some(index) = messages
-            .iter()
-            .position(|m| m.get("id").and_then(|v| v.as_str()) == Some(message_id))
-        {
-            messages[index] = message.clone();
-
-            // Rewrite all messages
-            let path = get_messages_path(app_handle.clone(), thread_id);
-            write_messages_to_file(&messages, &path)?;
-        }
-    }
+    helpers::update_message(app_handle, thread_id, message_id, message.clone()).await?;
     Ok(message)
 }
 
 /// Deletes a message from a thread's messages.jsonl file by message ID.
 /// Rewrites the entire messages.jsonl file for the thread.
 /// Uses a per-thread async lock to prevent race conditions and ensure file consistency.
+/// Returns an error if the message doesn't exist.
 #[tauri::command]
 pub async fn delete_message<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
     thread_id: String,
     message_id: String,
 ) -> Result<(), String> {
-    // Acquire per-thread lock before modifying
-    {
-        let lock = get_lock_for_thread(&thread_id).await;
-        let _guard = lock.lock().await;
+    helpers::delete_message(app_handle, &thread_id, &message_id).await
+}
 
-        let mut messages = read_messages_from_file(app_handle.clone(), &thread_id)?;
-        messages.retain(|m| m.get("id").and_then(|v| v.as_str()) != Some(message_id.as_str()));
+/// Rewrites a thread's messages.jsonl without blank or malformed lines.
+/// Uses the per-thread async lock, so it is safe to run while messages are being added.
+/// Returns the number of bytes reclaimed, 0 if the file was already compact.
+#[tauri::command]
+pub async fn compact_thread<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+) -> Result<u64, String> {
+    helpers::compact_thread(app_handle, &thread_id).await
+}
 
-        // Rewrite remaining messages
-        let path = get_messages_path(app_handle.clone(), &thread_id);
-        write_messages_to_file(&messages, &path)?;
-    }
+/// Compresses a thread's messages into messages.jsonl.gz to save disk space.
+/// The thread stays readable, and new messages are stored uncompressed until the next archive.
+#[tauri::command]
+pub async fn archive_thread<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+) -> Result<(), String> {
+    helpers::archive_thread(app_handle, &thread_id).await
+}
 
-    Ok(())
+/// Checks a thread's messages for lines that aren't valid JSON, lack an id or role, or
+/// reuse an id. Returns a report of the problems found without changing anything.
+#[tauri::command]
+pub async fn verify_thread<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+) -> Result<ThreadVerificationReport, String> {
+    helpers::verify_thread(app_handle, &thread_id).await
+}
+
+/// Moves the lines `verify_thread` reports to messages.jsonl.quarantine, keeping the
+/// valid messages. Returns the problems that were found.
+#[tauri::command]
+pub async fn repair_thread<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+) -> Result<ThreadVerificationReport, String> {
+    helpers::repair_thread(app_handle, &thread_id).await
 }
 
 /// Retrieves the first assistant associated with a thread.
@@ -223,11 +323,10 @@ pub async fn get_thread_assistant<R: Runtime>(
     thread_id: String,
 ) -> Result<serde_json::Value, String> {
     let path = get_thread_metadata_path(app_handle, &thread_id);
-    if !path.exists() {
+    if !thread_metadata_exists(&path) {
         return Err("Thread not found".to_string());
     }
-    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    let thread: serde_json::Value = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    let thread = read_thread_metadata(&path)?;
     if let Some(assistants) = thread.get("assistants").and_then(|a| a.as_array()) {
         if let Some(first) = assistants.get(0) {
             Ok(first.clone())
@@ -247,20 +346,15 @@ pub async fn create_thread_assistant<R: Runtime>(
     thread_id: String,
     assistant: serde_json::Value,
 ) -> Result<serde_json::Value, String> {
-    let path = get_thread_metadata_path(app_handle.clone(), &thread_id);
-    if !path.exists() {
-        return Err("Thread not found".to_string());
-    }
-    let mut thread: serde_json::Value = {
-        let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-        serde_json::from_str(&data).map_err(|e| e.to_string())?
-    };
-    if let Some(assistants) = thread.get_mut("assistants").and_then(|a| a.as_array_mut()) {
-        assistants.push(assistant.clone());
-    } else {
-        thread["assistants"] = serde_json::Value::Array(vec![assistant.clone()]);
-    }
-    update_thread_metadata(app_handle, &thread_id, &thread)?;
+    edit_thread_metadata(app_handle, &thread_id, |thread| {
+        if let Some(assistants) = thread.get_mut("assistants").and_then(|a| a.as_array_mut()) {
+            assistants.push(assistant.clone());
+        } else {
+            thread["assistants"] = serde_json::Value::Array(vec![assistant.clone()]);
+        }
+        Ok(())
+    })
+    .await?;
     Ok(assistant)
 }
 
@@ -272,29 +366,24 @@ pub async fn modify_thread_assistant<R: Runtime>(
     thread_id: String,
     assistant: serde_json::Value,
 ) -> Result<serde_json::Value, String> {
-    let path = get_thread_metadata_path(app_handle.clone(), &thread_id);
-    if !path.exists() {
-        return Err("Thread not found".to_string());
-    }
-    let mut thread: serde_json::Value = {
-        let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-        serde_json::from_str(&data).map_err(|e| e.to_string())?
-    };
     let assistant_id = assistant
         .get("id")
         .and_then(|v| v.as_str())
         .ok_or("Missing id")?;
-    if let Some(assistants) = thread
-        .get_mut("assistants")
-        .and_then(|a: &mut serde_json::Value| a.as_array_mut())
-    {
-        if let Some(index) = assistants
-            .iter()
-            .position(|a| a.get("id").and_then(|v| v.as_str()) == Some(assistant_id))
+    edit_thread_metadata(app_handle, &thread_id, |thread| {
+        if let Some(assistants) = thread
+            .get_mut("assistants")
+            .and_then(|a: &mut serde_json::Value| a.as_array_mut())
         {
-            assistants[index] = assistant.clone();
-            update_thread_metadata(app_handle, &thread_id, &thread)?;
+            if let Some(index) = assistants
+                .iter()
+                .position(|a| a.get("id").and_then(|v| v.as_str()) == Some(assistant_id))
+            {
+                assistants[index] = assistant.clone();
+            }
         }
-    }
+        Ok(())
+    })
+    .await?;
     Ok(assistant)
 }
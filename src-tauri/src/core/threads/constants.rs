@@ -1,4 +1,17 @@
+use std::time::Duration;
+
 // Thread Constants
 pub const THREADS_DIR: &str = "threads";
 pub const THREADS_FILE: &str = "thread.json";
 pub const MESSAGES_FILE: &str = "messages.jsonl";
+pub const BACKUP_SUFFIX: &str = ".bak";
+pub const ARCHIVE_SUFFIX: &str = ".gz"; // Older messages compressed by archive_thread
+pub const STAGED_SUFFIX: &str = ".staged"; // messages.jsonl while archive_thread folds it in
+pub const NEXT_ARCHIVE_SUFFIX: &str = ".next"; // Archive being written, replaces the current one
+pub const QUARANTINE_SUFFIX: &str = ".quarantine"; // Lines removed by repair_thread
+pub const REQUIRED_MESSAGE_FIELDS: [&str; 2] = ["id", "role"];
+pub const THREADS_INDEX_FILE: &str = "threads_index.json";
+pub const THREAD_PREVIEW_MAX_CHARS: usize = 200; // Length of the last-message preview in list_threads_with_preview
+pub const SEARCH_SNIPPET_CONTEXT_CHARS: usize = 40; // Characters kept on each side of a match
+pub const THREAD_LOCK_TIMEOUT: Duration = Duration::from_secs(30); // Before an operation reports the thread busy
+pub const REVERSE_READ_CHUNK_SIZE: u64 = 64 * 1024; // Bytes read at a time when scanning from the end
@@ -1,19 +1,36 @@
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
 use tauri::Runtime;
 
 // For async file write serialization
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{Mutex, OwnedMutexGuard};
 
-use super::utils::{get_messages_path, get_thread_metadata_path};
+use super::constants::{
+    ARCHIVE_SUFFIX, BACKUP_SUFFIX, MESSAGES_FILE, NEXT_ARCHIVE_SUFFIX, QUARANTINE_SUFFIX,
+    REQUIRED_MESSAGE_FIELDS, REVERSE_READ_CHUNK_SIZE, SEARCH_SNIPPET_CONTEXT_CHARS, STAGED_SUFFIX,
+    THREADS_FILE, THREAD_LOCK_TIMEOUT, THREAD_PREVIEW_MAX_CHARS,
+};
+use super::models::{
+    MessageCursorPage, MessagePage, MessageSearchResult, ThreadIndexEntry, ThreadLockError,
+    ThreadPreview, ThreadProblem, ThreadProblemKind, ThreadVerificationReport,
+};
+use super::utils::{
+    ensure_data_dirs, ensure_thread_dir_exists, get_data_dir, get_messages_path, get_thread_dir,
+    get_thread_index_path, get_thread_metadata_path,
+};
 
 // Global per-thread locks for message file writes
 pub static MESSAGE_LOCKS: Lazy<Mutex<HashMap<String, Arc<Mutex<()>>>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+// Serializes read-modify-write cycles of threads_index.json
+static THREAD_INDEX_LOCK: Lazy<std::sync::Mutex<()>> = Lazy::new(|| std::sync::Mutex::new(()));
+
 /// Get a lock for a specific thread to ensure thread-safe message file operations
 pub async fn get_lock_for_thread(thread_id: &str) -> Arc<Mutex<()>> {
     let mut locks = MESSAGE_LOCKS.lock().await;
@@ -25,63 +42,1264 @@ pub async fn get_lock_for_thread(thread_id: &str) -> Arc<Mutex<()>> {
     lock
 }
 
+/// Acquire the lock of a thread, giving up after `timeout`
+///
+/// Returns `ThreadLockError::Busy` while another operation holds it, e.g. a writer
+/// stuck on a full disk, so callers can ask the user to retry instead of hanging.
+pub async fn lock_thread(
+    thread_id: &str,
+    timeout: Duration,
+) -> Result<OwnedMutexGuard<()>, ThreadLockError> {
+    let lock = get_lock_for_thread(thread_id).await;
+    tokio::time::timeout(timeout, lock.lock_owned())
+        .await
+        .map_err(|_| ThreadLockError::Busy {
+            thread_id: thread_id.to_string(),
+            timeout_ms: timeout.as_millis() as u64,
+        })
+}
+
 /// Write messages to a thread's messages.jsonl file
+///
+/// The messages are written to a temporary file next to `path`, which then replaces
+/// `path` with a rename. A crash mid-write leaves the previous file intact. The
+/// messages replace the whole thread, so a thread archived by `archive_thread` stays
+/// compressed and is replaced as described in [`commit_archive`].
 pub fn write_messages_to_file(
     messages: &[serde_json::Value],
     path: &std::path::Path,
 ) -> Result<(), String> {
-    let mut file = File::create(path).map_err(|e| e.to_string())?;
-    for msg in messages {
-        let data = serde_json::to_string(msg).map_err(|e| e.to_string())?;
-        writeln!(file, "{}", data).map_err(|e| e.to_string())?;
+    let lines = messages
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    write_lines(path, &lines)
+}
+
+/// Replace all of a thread's messages with `lines`, see [`write_messages_to_file`]
+fn write_lines(path: &std::path::Path, lines: &[String]) -> Result<(), String> {
+    finish_archive(path)?;
+    if get_archive_path(path).exists() {
+        if path.exists() {
+            fs::rename(path, get_staged_path(path)).map_err(|e| e.to_string())?;
+        }
+        return commit_archive(path, lines);
+    }
+
+    write_file_atomically(path, |writer| {
+        for line in lines {
+            writeln!(writer, "{}", line).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    })
+}
+
+/// Compressed segment of older messages next to a thread's messages.jsonl
+fn get_archive_path(messages_path: &std::path::Path) -> std::path::PathBuf {
+    with_suffix(messages_path, ARCHIVE_SUFFIX)
+}
+
+/// messages.jsonl while it is being folded into the archive
+fn get_staged_path(messages_path: &std::path::Path) -> std::path::PathBuf {
+    with_suffix(messages_path, STAGED_SUFFIX)
+}
+
+/// New archive, complete once it exists, that replaces the archive and the staged file
+fn get_next_archive_path(messages_path: &std::path::Path) -> std::path::PathBuf {
+    with_suffix(&get_archive_path(messages_path), NEXT_ARCHIVE_SUFFIX)
+}
+
+/// Whether a thread has any messages on disk, archived or not
+fn has_messages(messages_path: &std::path::Path) -> bool {
+    messages_path.exists()
+        || get_archive_path(messages_path).exists()
+        || get_staged_path(messages_path).exists()
+        || get_next_archive_path(messages_path).exists()
+}
+
+/// Reader over the archived part of a thread, oldest first, `None` if there is none:
+/// the next archive if [`commit_archive`] got as far as writing it, otherwise the
+/// archive followed by the staged messages.
+fn open_archived_reader(messages_path: &std::path::Path) -> Result<Option<Box<dyn Read>>, String> {
+    let open = |path: &std::path::Path| File::open(path).map_err(|e| e.to_string());
+    let next_path = get_next_archive_path(messages_path);
+    if next_path.exists() {
+        return Ok(Some(Box::new(GzDecoder::new(open(&next_path)?))));
+    }
+
+    let archive_path = get_archive_path(messages_path);
+    let staged_path = get_staged_path(messages_path);
+    let mut reader: Option<Box<dyn Read>> = None;
+    if archive_path.exists() {
+        reader = Some(Box::new(GzDecoder::new(open(&archive_path)?)));
+    }
+    if staged_path.exists() {
+        let file = open(&staged_path)?;
+        reader = Some(match reader {
+            Some(archive) => Box::new(archive.chain(file)),
+            None => Box::new(file),
+        });
+    }
+    Ok(reader)
+}
+
+/// Reader over all of a thread's messages, oldest first: the archived part, see
+/// [`open_archived_reader`], followed by messages.jsonl. `None` if there are none.
+///
+/// The files change together while a thread is archived or rewritten, so callers
+/// must hold the thread lock.
+fn open_messages_reader(
+    messages_path: &std::path::Path,
+) -> Result<Option<Box<dyn BufRead>>, String> {
+    let mut reader = open_archived_reader(messages_path)?;
+    if messages_path.exists() {
+        let file = File::open(messages_path).map_err(|e| e.to_string())?;
+        reader = Some(match reader {
+            Some(archive) => Box::new(archive.chain(file)),
+            None => Box::new(file),
+        });
+    }
+    Ok(reader.map(|reader| Box::new(BufReader::new(reader)) as Box<dyn BufRead>))
+}
+
+/// Non-blank lines of `reader` that are valid JSON, malformed ones are logged and dropped
+fn read_valid_lines(reader: impl BufRead, path: &std::path::Path) -> Result<Vec<String>, String> {
+    let mut lines = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<serde_json::Value>(&line) {
+            Ok(_) => lines.push(line),
+            Err(e) => log::warn!(
+                "Dropping malformed line {} of {}: {}",
+                index + 1,
+                path.display(),
+                e
+            ),
+        }
+    }
+    Ok(lines)
+}
+
+/// Replace the archive and the staged messages with `lines`, compressed
+///
+/// messages.jsonl must already have been renamed to the staged file. The new archive is
+/// written to its own file first, and replaces the old one by a rename once the staged
+/// file is gone. Readers see the same messages at every step, and a crash at any point
+/// is completed by [`finish_archive`] without duplicating or losing messages.
+fn commit_archive(path: &std::path::Path, lines: &[String]) -> Result<(), String> {
+    let next_path = get_next_archive_path(path);
+    write_file_atomically(&next_path, |writer| {
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        for line in lines {
+            writeln!(encoder, "{}", line).map_err(|e| e.to_string())?;
+        }
+        encoder.finish().map_err(|e| e.to_string())?;
+        Ok(())
+    })?;
+
+    let staged_path = get_staged_path(path);
+    if staged_path.exists() {
+        fs::remove_file(&staged_path).map_err(|e| e.to_string())?;
+    }
+    fs::rename(&next_path, get_archive_path(path)).map_err(|e| e.to_string())
+}
+
+/// Complete an archive or rewrite interrupted by a crash, see [`commit_archive`]
+fn finish_archive(path: &std::path::Path) -> Result<(), String> {
+    let staged_path = get_staged_path(path);
+    if get_next_archive_path(path).exists() {
+        log::warn!("Finishing interrupted archive of {}", path.display());
+        if staged_path.exists() {
+            fs::remove_file(&staged_path).map_err(|e| e.to_string())?;
+        }
+        return fs::rename(get_next_archive_path(path), get_archive_path(path))
+            .map_err(|e| e.to_string());
+    }
+    if staged_path.exists() {
+        log::warn!("Finishing interrupted archive of {}", path.display());
+        let lines = match open_archived_reader(path)? {
+            Some(reader) => read_valid_lines(BufReader::new(reader), path)?,
+            None => vec![],
+        };
+        return commit_archive(path, &lines);
     }
     Ok(())
 }
 
-/// Read messages from a thread's messages.jsonl file
-pub fn read_messages_from_file<R: Runtime>(
+/// Compress all of a thread's messages into messages.jsonl.gz to save disk space
+///
+/// Messages added later are appended to a new, uncompressed messages.jsonl, and reads
+/// return the archive followed by it. Archiving again folds them into the archive.
+/// Blank and malformed lines are dropped like in `compact_thread`. Editing or deleting
+/// a message rewrites the archive.
+pub async fn archive_thread<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
     thread_id: &str,
-) -> Result<Vec<serde_json::Value>, String> {
-    let path = get_messages_path(app_handle, thread_id);
+) -> Result<(), String> {
+    let _guard = lock_thread(thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let path = get_messages_path(app_handle.clone(), thread_id);
+    finish_archive(&path)?;
     if !path.exists() {
-        return Ok(vec![]);
+        return Ok(()); // no messages, or nothing added since the last archive
+    }
+
+    fs::rename(&path, get_staged_path(&path)).map_err(|e| e.to_string())?;
+    let reader = open_archived_reader(&path)?.ok_or("Thread messages were removed")?;
+    let lines = read_valid_lines(BufReader::new(reader), &path)?;
+    commit_archive(&path, &lines)?;
+
+    let message_count = lines.len();
+    update_thread_index(app_handle, thread_id, move |entry| {
+        entry.message_count = message_count
+    })
+    .await;
+    Ok(())
+}
+
+/// Temporary file used while rewriting `path`, in the same directory so the final
+/// rename doesn't cross filesystems
+fn get_temp_path(path: &std::path::Path) -> std::path::PathBuf {
+    with_suffix(path, ".tmp")
+}
+
+fn with_suffix(path: &std::path::Path, suffix: &str) -> std::path::PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(suffix);
+    path.with_file_name(file_name)
+}
+
+/// Run `write` against a temporary file and rename it over `path` once it is flushed
+fn write_file_atomically(
+    path: &std::path::Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> Result<(), String>,
+) -> Result<(), String> {
+    let temp_path = get_temp_path(path);
+    let result = (|| {
+        let file = File::create(&temp_path).map_err(|e| e.to_string())?;
+        let mut writer = BufWriter::new(file);
+        write(&mut writer)?;
+        let file = writer.into_inner().map_err(|e| e.to_string())?;
+        file.sync_all().map_err(|e| e.to_string())?;
+        fs::rename(&temp_path, path).map_err(|e| e.to_string())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
     }
+    result
+}
+
+/// Append a single message to a thread's messages.jsonl file
+///
+/// Creates the thread directory and file if the thread is new. Takes the per-thread
+/// lock, so it must not be called while holding it.
+pub async fn append_message_to_file<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+    message: &serde_json::Value,
+) -> Result<(), String> {
+    ensure_thread_dir_exists(app_handle.clone(), thread_id)?;
+    let path = get_messages_path(app_handle.clone(), thread_id);
+    let data = serde_json::to_string(message).map_err(|e| e.to_string())?;
+
+    let _guard = lock_thread(thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| e.to_string())?;
+    writeln!(file, "{}", data).map_err(|e| e.to_string())?;
+
+    update_thread_index(app_handle, thread_id, |entry| entry.message_count += 1).await;
+    Ok(())
+}
+
+/// Replace the message with id `message_id` in a thread's messages.jsonl file
+///
+/// Returns an error if the thread has no message with that id.
+pub async fn update_message<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+    message_id: &str,
+    new_value: serde_json::Value,
+) -> Result<(), String> {
+    let _guard = lock_thread(thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut messages = read_messages_from_file(app_handle.clone(), thread_id)?;
+    let index = find_message_index(&messages, thread_id, message_id)?;
+    messages[index] = new_value;
+    write_messages_to_file(&messages, &get_messages_path(app_handle, thread_id))
+}
 
-    let file = File::open(&path).map_err(|e| {
-        eprintln!("Error opening file {}: {}", path.display(), e);
-        e.to_string()
+/// Remove the message with id `message_id` from a thread's messages.jsonl file
+///
+/// Returns an error if the thread has no message with that id.
+pub async fn delete_message<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+    message_id: &str,
+) -> Result<(), String> {
+    let _guard = lock_thread(thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut messages = read_messages_from_file(app_handle.clone(), thread_id)?;
+    let index = find_message_index(&messages, thread_id, message_id)?;
+    messages.remove(index);
+    write_messages_to_file(&messages, &get_messages_path(app_handle.clone(), thread_id))?;
+
+    let message_count = messages.len();
+    update_thread_index(app_handle, thread_id, move |entry| {
+        entry.message_count = message_count
+    })
+    .await;
+    Ok(())
+}
+
+/// Rewrite a thread's messages.jsonl without its blank and malformed lines
+///
+/// Holds the per-thread lock for the whole operation, so messages appended meanwhile
+/// wait for it and aren't lost. The file is left untouched if rewriting it wouldn't make
+/// it smaller. Returns the number of bytes reclaimed.
+pub async fn compact_thread<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+) -> Result<u64, String> {
+    let _guard = lock_thread(thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let path = get_messages_path(app_handle.clone(), thread_id);
+    finish_archive(&path)?;
+    // Archived threads were compacted when archived
+    if !path.exists() || get_archive_path(&path).exists() {
+        return Ok(0);
+    }
+    let size_before = fs::metadata(&path).map_err(|e| e.to_string())?.len();
+
+    let messages = read_messages_lenient(app_handle.clone(), thread_id)?;
+    let lines = messages
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let size_after: u64 = lines.iter().map(|line| line.len() as u64 + 1).sum();
+    if size_after >= size_before {
+        return Ok(0);
+    }
+
+    write_file_atomically(&path, |writer| {
+        for line in &lines {
+            writeln!(writer, "{}", line).map_err(|e| e.to_string())?;
+        }
+        Ok(())
     })?;
-    let reader = BufReader::new(file);
+    let message_count = messages.len();
+    update_thread_index(app_handle, thread_id, move |entry| {
+        entry.message_count = message_count
+    })
+    .await;
+
+    let reclaimed = size_before - size_after;
+    log::info!(
+        "Compacted thread {}, reclaimed {} bytes",
+        thread_id,
+        reclaimed
+    );
+    Ok(reclaimed)
+}
+
+/// Check every line of a thread, returning the report along with the good and bad lines
+fn scan_thread(
+    path: &std::path::Path,
+) -> Result<(ThreadVerificationReport, Vec<String>, Vec<String>), String> {
+    let mut report = ThreadVerificationReport::default();
+    let (mut good_lines, mut bad_lines) = (Vec::new(), Vec::new());
+    let Some(reader) = open_messages_reader(path)? else {
+        return Ok((report, good_lines, bad_lines));
+    };
+
+    let mut seen_ids = std::collections::HashSet::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let new_problem = |kind, message_id: Option<&str>, detail: String| ThreadProblem {
+            line: index + 1,
+            kind,
+            message_id: message_id.map(String::from),
+            detail,
+        };
+
+        let problem = match serde_json::from_str::<serde_json::Value>(&line) {
+            Err(e) => Some(new_problem(
+                ThreadProblemKind::InvalidJson,
+                None,
+                e.to_string(),
+            )),
+            Ok(message) => {
+                let id = message.get("id").and_then(|id| id.as_str());
+                let missing: Vec<&str> = REQUIRED_MESSAGE_FIELDS
+                    .iter()
+                    .copied()
+                    .filter(|field| message.get(*field).map_or(true, |v| v.is_null()))
+                    .collect();
+                if !missing.is_empty() {
+                    let detail = format!("Missing {}", missing.join(", "));
+                    Some(new_problem(ThreadProblemKind::MissingField, id, detail))
+                } else if !seen_ids.insert(id.unwrap_or_default().to_string()) {
+                    let detail = "Same id as an earlier message".to_string();
+                    Some(new_problem(ThreadProblemKind::DuplicateId, id, detail))
+                } else {
+                    None
+                }
+            }
+        };
+        match problem {
+            Some(problem) => {
+                report.problems.push(problem);
+                bad_lines.push(line);
+            }
+            None => {
+                report.valid_messages += 1;
+                good_lines.push(line);
+            }
+        }
+    }
+    Ok((report, good_lines, bad_lines))
+}
+
+/// Check that every line of a thread's messages is a JSON message with an id and a role,
+/// and that no id is used twice
+///
+/// Blank lines aren't reported. Use [`repair_thread`] to set the bad lines aside.
+pub async fn verify_thread<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+) -> Result<ThreadVerificationReport, String> {
+    let _guard = lock_thread(thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let (report, _, _) = scan_thread(&get_messages_path(app_handle, thread_id))?;
+    Ok(report)
+}
+
+/// Move the lines reported by [`verify_thread`] to messages.jsonl.quarantine
+///
+/// The quarantine file is appended to, so lines from earlier repairs are kept for
+/// support to inspect. Returns the problems found, the thread is left untouched if
+/// there are none.
+pub async fn repair_thread<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+) -> Result<ThreadVerificationReport, String> {
+    let _guard = lock_thread(thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let path = get_messages_path(app_handle.clone(), thread_id);
+    let (report, good_lines, bad_lines) = scan_thread(&path)?;
+    if bad_lines.is_empty() {
+        return Ok(report);
+    }
+
+    // Quarantine first, so a failure can't lose the bad lines
+    let mut quarantine = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(with_suffix(&path, QUARANTINE_SUFFIX))
+        .map_err(|e| e.to_string())?;
+    for line in &bad_lines {
+        writeln!(quarantine, "{}", line).map_err(|e| e.to_string())?;
+    }
+    quarantine.sync_all().map_err(|e| e.to_string())?;
+
+    write_lines(&path, &good_lines)?;
+
+    log::warn!(
+        "Repaired thread {}, quarantined {} lines",
+        thread_id,
+        bad_lines.len()
+    );
+    let message_count = good_lines.len();
+    update_thread_index(app_handle, thread_id, move |entry| {
+        entry.message_count = message_count
+    })
+    .await;
+    Ok(report)
+}
+
+fn find_message_index(
+    messages: &[serde_json::Value],
+    thread_id: &str,
+    message_id: &str,
+) -> Result<usize, String> {
+    messages
+        .iter()
+        .position(|m| m.get("id").and_then(|v| v.as_str()) == Some(message_id))
+        .ok_or_else(|| format!("Message {} not found in thread {}", message_id, thread_id))
+}
+
+/// Read messages from a thread's messages.jsonl file
+///
+/// Fails on the first line that isn't valid JSON. Callers that rewrite the file should
+/// use this so malformed lines aren't silently dropped.
+pub fn read_messages_from_file<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+) -> Result<Vec<serde_json::Value>, String> {
+    read_messages(app_handle, thread_id, true)
+}
+
+/// Read messages from a thread's messages.jsonl file, skipping malformed lines
+///
+/// Each skipped line is logged with its line number, so one half-written line doesn't
+/// make the whole conversation unreadable.
+pub fn read_messages_lenient<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+) -> Result<Vec<serde_json::Value>, String> {
+    read_messages(app_handle, thread_id, false)
+}
+
+fn read_messages<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+    strict: bool,
+) -> Result<Vec<serde_json::Value>, String> {
+    let path = get_messages_path(app_handle, thread_id);
+    let reader = match open_messages_reader(&path) {
+        Ok(Some(reader)) => reader,
+        Ok(None) => return Ok(vec![]),
+        Err(e) => {
+            eprintln!("Error opening file {}: {}", path.display(), e);
+            return Err(e);
+        }
+    };
 
     let mut messages = Vec::new();
-    for line in reader.lines() {
+    for (index, line) in reader.lines().enumerate() {
         let line = line.map_err(|e| {
             eprintln!("Error reading line from file {}: {}", path.display(), e);
             e.to_string()
         })?;
-        let message: serde_json::Value = serde_json::from_str(&line).map_err(|e| {
-            eprintln!(
-                "Error parsing JSON from line in file {}: {}",
+        if !strict && line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(message) => messages.push(message),
+            Err(e) if strict => {
+                eprintln!(
+                    "Error parsing JSON from line in file {}: {}",
+                    path.display(),
+                    e
+                );
+                return Err(format!(
+                    "Line {} of thread {} is not valid JSON ({}), run repair_thread to quarantine the malformed lines",
+                    index + 1,
+                    thread_id,
+                    e
+                ));
+            }
+            Err(e) => log::warn!(
+                "Skipping malformed line {} in {}: {}",
+                index + 1,
                 path.display(),
                 e
-            );
-            e.to_string()
-        })?;
-        messages.push(message);
+            ),
+        }
     }
 
     Ok(messages)
 }
 
-/// Update thread metadata by writing to thread.json
-pub fn update_thread_metadata<R: Runtime>(
+/// Read a window of a thread's messages without parsing the rest of the file
+///
+/// `offset` counts from the newest message, so offset 0 with limit 50 returns the
+/// 50 most recent messages. Messages in the page keep their chronological order, and
+/// `total` is the number of messages in the thread. Malformed lines in the window are
+/// skipped like in [`read_messages_lenient`].
+pub fn read_messages_paginated<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+    offset: usize,
+    limit: usize,
+) -> Result<MessagePage, String> {
+    let path = get_messages_path(app_handle, thread_id);
+    if !has_messages(&path) {
+        return Ok(MessagePage {
+            messages: vec![],
+            total: 0,
+        });
+    }
+
+    let open_lines = || -> Result<_, String> {
+        let reader = open_messages_reader(&path)?.ok_or("Thread messages were removed")?;
+        Ok(reader
+            .lines()
+            .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty())))
+    };
+
+    // First pass only counts lines, second pass parses the window
+    let mut total: usize = 0;
+    for line in open_lines()? {
+        line.map_err(|e| e.to_string())?;
+        total += 1;
+    }
+
+    let end = total.saturating_sub(offset);
+    let start = end.saturating_sub(limit);
+    let mut messages = Vec::with_capacity(end - start);
+    for (index, line) in open_lines()?.enumerate().skip(start).take(end - start) {
+        let line = line.map_err(|e| e.to_string())?;
+        match serde_json::from_str(&line) {
+            Ok(message) => messages.push(message),
+            Err(e) => log::warn!(
+                "Skipping malformed message {} in {}: {}",
+                index + 1,
+                path.display(),
+                e
+            ),
+        }
+    }
+
+    Ok(MessagePage { messages, total })
+}
+
+fn is_blank(line: &[u8]) -> bool {
+    line.iter().all(u8::is_ascii_whitespace)
+}
+
+/// Call `visit` with each non-empty line of the file at `path`, last line first, until
+/// it returns `false`. Returns whether `visit` stopped the iteration.
+///
+/// The file is read backwards in `REVERSE_READ_CHUNK_SIZE` chunks, so only the lines
+/// visited are loaded.
+fn for_each_line_reversed(
+    path: &std::path::Path,
+    mut visit: impl FnMut(&[u8]) -> bool,
+) -> Result<bool, String> {
+    use std::io::{Seek, SeekFrom};
+
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let mut position = file.seek(SeekFrom::End(0)).map_err(|e| e.to_string())?;
+    // Start of the line that continues into the chunk read last
+    let mut partial_line: Vec<u8> = Vec::new();
+    while position > 0 {
+        let chunk_size = REVERSE_READ_CHUNK_SIZE.min(position);
+        position -= chunk_size;
+        file.seek(SeekFrom::Start(position))
+            .map_err(|e| e.to_string())?;
+        let mut chunk = vec![0; chunk_size as usize];
+        file.read_exact(&mut chunk).map_err(|e| e.to_string())?;
+        chunk.extend_from_slice(&partial_line);
+
+        let mut end = chunk.len();
+        while let Some(start) = chunk[..end].iter().rposition(|&b| b == b'\n') {
+            let line = &chunk[start + 1..end];
+            if !is_blank(line) && !visit(line) {
+                return Ok(true);
+            }
+            end = start;
+        }
+        chunk.truncate(end);
+        partial_line = chunk;
+    }
+    Ok(!is_blank(&partial_line) && !visit(&partial_line))
+}
+
+/// Read up to `limit` messages older than `before_message_id`, newest first
+///
+/// Starts from the newest message when `before_message_id` is `None`. The file is
+/// scanned from the end, so loading recent pages doesn't read the whole thread, except
+/// for archived messages which have to be decompressed.
+/// Malformed lines are skipped like in [`read_messages_lenient`]. Returns an error if
+/// the thread has no message with id `before_message_id`.
+pub fn read_messages_before<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+    before_message_id: Option<&str>,
+    limit: usize,
+) -> Result<MessageCursorPage, String> {
+    let path = get_messages_path(app_handle, thread_id);
+    if !has_messages(&path) {
+        return match before_message_id {
+            Some(id) => Err(format!("Message {} not found in thread {}", id, thread_id)),
+            None => Ok(MessageCursorPage {
+                messages: vec![],
+                next_cursor: None,
+            }),
+        };
+    }
+
+    let mut found_cursor = before_message_id.is_none();
+    let mut messages: Vec<serde_json::Value> = Vec::new();
+    let mut has_more = false;
+    let mut visit = |line: &[u8]| {
+        let message: serde_json::Value = match serde_json::from_slice(line) {
+            Ok(message) => message,
+            Err(e) => {
+                log::warn!("Skipping malformed message in {}: {}", path.display(), e);
+                return true;
+            }
+        };
+        if !found_cursor {
+            found_cursor = message.get("id").and_then(|id| id.as_str()) == before_message_id;
+            return true;
+        }
+        if messages.len() == limit {
+            has_more = true;
+            return false;
+        }
+        messages.push(message);
+        true
+    };
+
+    let stopped = path.exists() && for_each_line_reversed(&path, &mut visit)?;
+    if !stopped {
+        // Compressed data can't be read backwards, so the archive is decompressed whole
+        let mut archived = Vec::new();
+        if let Some(mut reader) = open_archived_reader(&path)? {
+            reader
+                .read_to_end(&mut archived)
+                .map_err(|e| e.to_string())?;
+        }
+        for line in archived.rsplit(|&b| b == b'\n') {
+            if !is_blank(line) && !visit(line) {
+                break;
+            }
+        }
+    }
+
+    if !found_cursor {
+        return Err(format!(
+            "Message {} not found in thread {}",
+            before_message_id.unwrap_or_default(),
+            thread_id
+        ));
+    }
+    let next_cursor = if has_more {
+        match messages.last() {
+            Some(oldest) => oldest
+                .get("id")
+                .and_then(|id| id.as_str())
+                .map(String::from),
+            None => before_message_id.map(String::from),
+        }
+    } else {
+        None
+    };
+    Ok(MessageCursorPage {
+        messages,
+        next_cursor,
+    })
+}
+
+/// Concatenate the text parts of a message's content array
+pub fn get_message_text(message: &serde_json::Value) -> String {
+    let Some(content) = message.get("content").and_then(|c| c.as_array()) else {
+        return String::new();
+    };
+    content
+        .iter()
+        .filter_map(|part| match part.get("text")? {
+            serde_json::Value::String(text) => Some(text.as_str()),
+            text => text.get("value")?.as_str(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Character offset of the first case-insensitive occurrence of `query` in `text`
+fn find_case_insensitive(text: &str, query: &str) -> Option<usize> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return None;
+    }
+    text.char_indices().position(|(start, _)| {
+        let mut candidate = text[start..].chars().flat_map(char::to_lowercase);
+        query.iter().all(|c| candidate.next() == Some(*c))
+    })
+}
+
+/// Search a thread's messages for `query`, case-insensitively
+///
+/// Messages are read one line at a time, so the whole file is never held in memory.
+/// Returns the first match of each matching message with a short snippet around it.
+pub fn search_messages<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+    query: &str,
+) -> Result<Vec<MessageSearchResult>, String> {
+    let path = get_messages_path(app_handle, thread_id);
+    let Some(reader) = open_messages_reader(&path)? else {
+        return Ok(vec![]);
+    };
+
+    let mut results = Vec::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        let Ok(message) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        let text = get_message_text(&message);
+        let Some(offset) = find_case_insensitive(&text, query) else {
+            continue;
+        };
+
+        let start = offset.saturating_sub(SEARCH_SNIPPET_CONTEXT_CHARS);
+        let len = SEARCH_SNIPPET_CONTEXT_CHARS * 2 + query.chars().count();
+        results.push(MessageSearchResult {
+            message_id: message
+                .get("id")
+                .and_then(|id| id.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            snippet: text.chars().skip(start).take(len).collect(),
+            offset,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Render a thread as a Markdown transcript
+///
+/// The header is taken from thread.json, followed by each message under a bold role
+/// header. Message text is copied verbatim, so code fences are preserved.
+pub fn export_thread_markdown<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+) -> Result<String, String> {
+    let path = get_thread_metadata_path(app_handle.clone(), thread_id);
+    let thread = read_thread_metadata(&path)?;
+
+    let title = thread
+        .get("title")
+        .and_then(|t| t.as_str())
+        .filter(|t| !t.is_empty())
+        .unwrap_or("Untitled thread");
+    let mut markdown = format!("# {}\n\n- Thread: `{}`\n", title, thread_id);
+    if let Some(assistant) = thread
+        .get("assistants")
+        .and_then(|a| a.as_array())
+        .and_then(|a| a.first())
+    {
+        if let Some(name) = assistant.get("assistant_name").and_then(|n| n.as_str()) {
+            markdown.push_str(&format!("- Assistant: {}\n", name));
+        }
+        if let Some(model) = assistant.pointer("/model/id").and_then(|m| m.as_str()) {
+            markdown.push_str(&format!("- Model: {}\n", model));
+        }
+    }
+    markdown.push_str("\n---\n");
+
+    for message in read_messages_lenient(app_handle, thread_id)? {
+        let text = get_message_text(&message);
+        if text.trim().is_empty() {
+            continue;
+        }
+        let role = message.get("role").and_then(|r| r.as_str()).unwrap_or("");
+        let mut chars = role.chars();
+        let role: String = match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => "Unknown".to_string(),
+        };
+        markdown.push_str(&format!("\n**{}**\n\n{}\n", role, text.trim_end()));
+    }
+
+    Ok(markdown)
+}
+
+/// Whether a thread has metadata [`read_thread_metadata`] can read
+pub fn thread_metadata_exists(path: &std::path::Path) -> bool {
+    path.exists() || with_suffix(path, BACKUP_SUFFIX).exists()
+}
+
+/// Read a thread's thread.json, falling back to thread.json.bak if it is missing
+///
+/// thread.json is only ever replaced by a rename, but a crash while the thread was
+/// being created can leave just the backup. Nothing is written here, so it is safe to
+/// call without the thread lock.
+pub fn read_thread_metadata(path: &std::path::Path) -> Result<serde_json::Value, String> {
+    let backup_path = with_suffix(path, BACKUP_SUFFIX);
+    let data = match fs::read_to_string(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && backup_path.exists() => {
+            log::warn!("{} is missing, reading its backup instead", path.display());
+            fs::read_to_string(&backup_path)
+        }
+        result => result,
+    }
+    .map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| e.to_string())
+}
+
+/// Write thread.json, keeping the previous version as thread.json.bak. The caller must
+/// hold the thread lock, the backup copy and the temporary file aren't safe otherwise.
+async fn write_thread_metadata<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
     thread_id: &str,
     thread: &serde_json::Value,
 ) -> Result<(), String> {
-    let path = get_thread_metadata_path(app_handle, thread_id);
+    let path = get_thread_metadata_path(app_handle.clone(), thread_id);
     let data = serde_json::to_string_pretty(thread).map_err(|e| e.to_string())?;
-    fs::write(path, data).map_err(|e| e.to_string())?;
+    if path.exists() {
+        let backup = fs::read(&path).map_err(|e| e.to_string())?;
+        write_file_atomically(&with_suffix(&path, BACKUP_SUFFIX), |writer| {
+            writer.write_all(&backup).map_err(|e| e.to_string())
+        })?;
+    }
+    write_file_atomically(&path, |writer| {
+        writer.write_all(data.as_bytes()).map_err(|e| e.to_string())
+    })?;
+
+    let title = get_thread_title(thread);
+    let updated = thread.get("updated").and_then(|u| u.as_i64()).unwrap_or(0);
+    update_thread_index(app_handle, thread_id, move |entry| {
+        entry.title = title;
+        entry.updated = updated;
+    })
+    .await;
+    Ok(())
+}
+
+/// Update thread metadata by writing to thread.json
+///
+/// The previous thread.json is kept as thread.json.bak, and the new one is written
+/// atomically, so the last known good metadata can always be restored. Takes the
+/// per-thread lock, so concurrent updates of the same thread don't interleave.
+pub async fn update_thread_metadata<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+    thread: &serde_json::Value,
+) -> Result<(), String> {
+    let _guard = lock_thread(thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+    write_thread_metadata(app_handle, thread_id, thread).await
+}
+
+/// Read thread.json, apply `edit` and write it back like [`update_thread_metadata`],
+/// all under the per-thread lock so no concurrent update is lost
+///
+/// The file is left untouched if `edit` doesn't change anything. Returns an error if
+/// the thread doesn't exist.
+pub async fn edit_thread_metadata<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+    edit: impl FnOnce(&mut serde_json::Value) -> Result<(), String>,
+) -> Result<(), String> {
+    let _guard = lock_thread(thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let path = get_thread_metadata_path(app_handle.clone(), thread_id);
+    if !thread_metadata_exists(&path) {
+        return Err("Thread not found".to_string());
+    }
+    let thread = read_thread_metadata(&path)?;
+    let mut edited = thread.clone();
+    edit(&mut edited)?;
+    if edited == thread {
+        return Ok(());
+    }
+    write_thread_metadata(app_handle, thread_id, &edited).await
+}
+
+/// Swap thread.json with its backup, undoing the last metadata update
+///
+/// Both files are replaced atomically, thread.json first, so it always exists. A crash
+/// between the two writes leaves the restored metadata in both. Calling it again swaps
+/// them back. Returns an error if there is no backup.
+pub async fn restore_thread_metadata<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+) -> Result<(), String> {
+    let _guard = lock_thread(thread_id, THREAD_LOCK_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let path = get_thread_metadata_path(app_handle.clone(), thread_id);
+    let backup_path = with_suffix(&path, BACKUP_SUFFIX);
+    if !backup_path.exists() {
+        return Err(format!("No metadata backup for thread {}", thread_id));
+    }
+
+    let backup = fs::read(&backup_path).map_err(|e| e.to_string())?;
+    let thread: serde_json::Value = serde_json::from_slice(&backup).map_err(|e| e.to_string())?;
+    let current = match fs::read(&path) {
+        Ok(current) => Some(current),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.to_string()),
+    };
+
+    write_file_atomically(&path, |writer| {
+        writer.write_all(&backup).map_err(|e| e.to_string())
+    })?;
+    if let Some(current) = current {
+        write_file_atomically(&backup_path, |writer| {
+            writer.write_all(&current).map_err(|e| e.to_string())
+        })?;
+    }
+
+    let title = get_thread_title(&thread);
+    let updated = thread.get("updated").and_then(|u| u.as_i64()).unwrap_or(0);
+    update_thread_index(app_handle, thread_id, move |entry| {
+        entry.title = title;
+        entry.updated = updated;
+    })
+    .await;
+    Ok(())
+}
+
+fn get_thread_title(thread: &serde_json::Value) -> String {
+    thread
+        .get("title")
+        .and_then(|t| t.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Number of messages in a thread's messages.jsonl file
+fn count_messages(path: &std::path::Path) -> Result<usize, String> {
+    let Some(reader) = open_messages_reader(path)? else {
+        return Ok(0);
+    };
+    let mut count = 0;
+    for line in reader.lines() {
+        if !line.map_err(|e| e.to_string())?.trim().is_empty() {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Read threads_index.json, or an empty index if it doesn't exist yet
+pub fn read_thread_index<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+) -> Result<BTreeMap<String, ThreadIndexEntry>, String> {
+    let path = get_thread_index_path(app_handle);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| e.to_string())
+}
+
+fn write_thread_index(
+    path: &std::path::Path,
+    index: &BTreeMap<String, ThreadIndexEntry>,
+) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(index).map_err(|e| e.to_string())?;
+    write_file_atomically(path, |writer| {
+        writer.write_all(data.as_bytes()).map_err(|e| e.to_string())
+    })
+}
+
+/// Apply `update` to a thread's entry in threads_index.json
+///
+/// Called after the thread's files were written, which are the source of truth, so
+/// this is best-effort: a failed update is logged and deletes the index, and the next
+/// reader rebuilds it. Runs on a blocking thread, as a missing index means scanning
+/// every thread folder.
+async fn update_thread_index<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+    update: impl FnOnce(&mut ThreadIndexEntry) + Send + 'static,
+) {
+    let thread_id = thread_id.to_string();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let _guard = THREAD_INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let path = get_thread_index_path(app_handle.clone());
+        apply_thread_index_update(app_handle, &path, &thread_id, update).map_err(|e| {
+            if path.exists() {
+                let _ = fs::remove_file(&path);
+            }
+            format!("thread {thread_id}: {e}")
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+
+    if let Err(e) = result {
+        log::warn!("Failed to update the thread index, {}", e);
+    }
+}
+
+/// Does the work of [`update_thread_index`], the caller must hold `THREAD_INDEX_LOCK`
+///
+/// Entries that aren't in the index yet, or a missing or unreadable index, are built
+/// from the files, which already include the change.
+fn apply_thread_index_update<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    path: &std::path::Path,
+    thread_id: &str,
+    update: impl FnOnce(&mut ThreadIndexEntry),
+) -> Result<(), String> {
+    let existing = if path.exists() {
+        read_thread_index(app_handle.clone())
+            .map_err(|e| log::warn!("Rebuilding unreadable thread index: {}", e))
+            .ok()
+    } else {
+        None
+    };
+    let index = match existing {
+        Some(mut index) => {
+            match index.get_mut(thread_id) {
+                Some(entry) => update(entry),
+                None => {
+                    let thread_dir = get_thread_dir(app_handle.clone(), thread_id);
+                    if let Some(entry) = read_thread_index_entry(&thread_dir)? {
+                        index.insert(thread_id.to_string(), entry);
+                    }
+                }
+            }
+            index
+        }
+        None => build_thread_index(app_handle)?,
+    };
+
+    write_thread_index(path, &index)
+}
+
+/// Remove a deleted thread from threads_index.json
+pub fn remove_from_thread_index<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: &str,
+) -> Result<(), String> {
+    let _guard = THREAD_INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let path = get_thread_index_path(app_handle.clone());
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut index = read_thread_index(app_handle)?;
+    if index.remove(thread_id).is_some() {
+        write_thread_index(&path, &index)?;
+    }
     Ok(())
 }
+
+/// List every thread with the text of its last message, most recently updated first
+///
+/// Only the end of each messages.jsonl is read, see [`read_messages_before`], under the
+/// thread's lock. Threads whose thread.json is missing or invalid, or whose messages
+/// can't be read, are skipped with a warning.
+pub async fn list_threads_with_preview<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+) -> Result<Vec<ThreadPreview>, String> {
+    ensure_data_dirs(app_handle.clone())?;
+    let mut previews = Vec::new();
+    for entry in fs::read_dir(get_data_dir(app_handle.clone())).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let Some(id) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let thread = match read_thread_metadata(&path.join(THREADS_FILE)) {
+            Ok(thread) => thread,
+            Err(e) => {
+                log::warn!("Skipping thread {} with unreadable thread.json: {}", id, e);
+                continue;
+            }
+        };
+        let last_message = match lock_thread(id, THREAD_LOCK_TIMEOUT)
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|_guard| read_messages_before(app_handle.clone(), id, None, 1))
+        {
+            Ok(page) => page.messages.into_iter().next(),
+            Err(e) => {
+                log::warn!("Skipping thread {} with unreadable messages: {}", id, e);
+                continue;
+            }
+        };
+
+        previews.push(ThreadPreview {
+            id: id.to_string(),
+            title: get_thread_title(&thread),
+            updated: thread.get("updated").and_then(|u| u.as_i64()).unwrap_or(0),
+            last_message: last_message.as_ref().map(|message| {
+                get_message_text(message)
+                    .chars()
+                    .take(THREAD_PREVIEW_MAX_CHARS)
+                    .collect()
+            }),
+            last_message_role: last_message
+                .as_ref()
+                .and_then(|message| message.get("role")?.as_str())
+                .map(String::from),
+        });
+    }
+    previews.sort_by_key(|preview| std::cmp::Reverse(preview.updated));
+    Ok(previews)
+}
+
+/// Index entry of the thread stored in `thread_dir`, if it has a valid thread.json
+fn read_thread_index_entry(
+    thread_dir: &std::path::Path,
+) -> Result<Option<ThreadIndexEntry>, String> {
+    let Some(id) = thread_dir.file_name().and_then(|name| name.to_str()) else {
+        return Ok(None);
+    };
+    let metadata_path = thread_dir.join(THREADS_FILE);
+    if !thread_metadata_exists(&metadata_path) {
+        return Ok(None);
+    }
+    let Ok(thread) = read_thread_metadata(&metadata_path) else {
+        log::warn!("Skipping invalid thread file in {}", thread_dir.display());
+        return Ok(None);
+    };
+    Ok(Some(ThreadIndexEntry {
+        id: id.to_string(),
+        title: get_thread_title(&thread),
+        updated: thread.get("updated").and_then(|u| u.as_i64()).unwrap_or(0),
+        message_count: count_messages(&thread_dir.join(MESSAGES_FILE))?,
+    }))
+}
+
+/// Scan every thread folder and collect their index entries
+fn build_thread_index<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+) -> Result<BTreeMap<String, ThreadIndexEntry>, String> {
+    ensure_data_dirs(app_handle.clone())?;
+    let mut index = BTreeMap::new();
+    for entry in fs::read_dir(get_data_dir(app_handle)).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if let Some(entry) = read_thread_index_entry(&path)? {
+            index.insert(entry.id.clone(), entry);
+        }
+    }
+    Ok(index)
+}
+
+/// Regenerate threads_index.json from the thread folders
+pub fn rebuild_thread_index<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+) -> Result<Vec<ThreadIndexEntry>, String> {
+    let _guard = THREAD_INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let index = build_thread_index(app_handle.clone())?;
+    write_thread_index(&get_thread_index_path(app_handle), &index)?;
+    Ok(index.into_values().collect())
+}
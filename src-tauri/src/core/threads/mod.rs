@@ -8,6 +8,7 @@
    - All operations that write or modify messages for a thread are protected by a global, per-thread asynchronous lock.
    - This design ensures that only one operation can write to a thread's messages.jsonl file at a time, preventing race conditions.
    - As a result, the messages.jsonl file for each thread is always consistent and never corrupted, even under concurrent access.
+   - Commands that read messages take the same lock, so they never see a thread halfway through being archived or rewritten.
 */
 
 pub mod commands;
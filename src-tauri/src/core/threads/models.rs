@@ -101,3 +101,77 @@ pub struct ThreadState {
     pub error: Option<String>,
     pub last_message: Option<String>,
 }
+
+/// A window of a thread's messages, returned by `list_messages_paginated`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessagePage {
+    pub messages: Vec<serde_json::Value>,
+    pub total: usize,
+}
+
+/// A page of a thread's messages going back in time, returned by `list_messages_before`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessageCursorPage {
+    pub messages: Vec<serde_json::Value>, // newest first
+    /// Id of the oldest message in the page, to pass as `before_message_id` for the next
+    /// page. `None` once the beginning of the thread is reached.
+    pub next_cursor: Option<String>,
+}
+
+/// A message matching a `search_messages` query
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessageSearchResult {
+    pub message_id: String,
+    pub snippet: String,
+    pub offset: usize, // character offset of the match in the message text
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreadProblemKind {
+    InvalidJson,
+    MissingField,
+    DuplicateId,
+}
+
+/// A bad line found by `verify_thread`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThreadProblem {
+    pub line: usize, // 1-based, counting archived messages first
+    pub kind: ThreadProblemKind,
+    pub message_id: Option<String>,
+    pub detail: String,
+}
+
+/// Result of checking a thread's messages with `verify_thread` or `repair_thread`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ThreadVerificationReport {
+    pub valid_messages: usize,
+    pub problems: Vec<ThreadProblem>,
+}
+
+/// Summary of a thread kept in threads_index.json
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ThreadIndexEntry {
+    pub id: String,
+    pub title: String,
+    pub updated: i64,
+    pub message_count: usize,
+}
+
+/// A thread with a preview of its last message, for the thread sidebar
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ThreadPreview {
+    pub id: String,
+    pub title: String,
+    pub updated: i64,
+    pub last_message: Option<String>, // text of the last message, truncated
+    pub last_message_role: Option<String>,
+}
+
+/// Error returned by `lock_thread` when another operation holds the thread for too long
+#[derive(Debug, thiserror::Error)]
+pub enum ThreadLockError {
+    #[error("Thread {thread_id} is busy, another operation has held it for over {timeout_ms}ms. Try again later.")]
+    Busy { thread_id: String, timeout_ms: u64 },
+}
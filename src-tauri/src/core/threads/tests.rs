@@ -1,25 +1,36 @@
 use crate::core::app::commands::get_jan_data_folder_path;
 
 use super::commands::*;
+use super::helpers::{
+    self, append_message_to_file, read_messages_before, read_messages_from_file,
+    read_messages_lenient, read_messages_paginated, search_messages, write_messages_to_file,
+};
+use super::models::{ThreadIndexEntry, ThreadLockError};
 use serde_json::json;
 use std::fs;
 use std::path::PathBuf;
-use tauri::test::{mock_app, MockRuntime};
+use tauri::{
+    test::{mock_app, MockRuntime},
+    App,
+};
+use tokio::sync::{Mutex, MutexGuard};
 
-// Helper to create a mock app handle with a temp data dir
-fn mock_app_with_temp_data_dir() -> (tauri::App<MockRuntime>, PathBuf) {
+// Every mock app shares the same test-data folder, which the tests remove when done
+static TEST_DATA_DIR_LOCK: Mutex<()> = Mutex::const_new(());
+
+// Helper to create a mock app handle with the test data dir, the guard keeps other
+// thread tests from touching the folder until the test ends
+async fn mock_app_with_temp_data_dir() -> (App<MockRuntime>, PathBuf, MutexGuard<'static, ()>) {
+    let data_lock = TEST_DATA_DIR_LOCK.lock().await;
     let app = mock_app();
     let data_dir = get_jan_data_folder_path(app.handle().clone());
     println!("Mock app data dir: {}", data_dir.display());
-    // Patch get_data_dir to use temp dir (requires get_data_dir to be overridable or injectable)
-    // For now, we assume get_data_dir uses tauri::api::path::app_data_dir(&app_handle)
-    // and that we can set the environment variable to redirect it.
-    (app, data_dir)
+    (app, data_dir, data_lock)
 }
 
 #[tokio::test]
 async fn test_create_and_list_threads() {
-    let (app, data_dir) = mock_app_with_temp_data_dir();
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
     // Create a thread
     let thread = json!({
         "object": "thread",
@@ -44,7 +55,7 @@ async fn test_create_and_list_threads() {
 
 #[tokio::test]
 async fn test_create_and_list_messages() {
-    let (app, data_dir) = mock_app_with_temp_data_dir();
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
     // Create a thread first
     let thread = json!({
         "object": "thread",
@@ -91,7 +102,7 @@ async fn test_create_and_list_messages() {
 
 #[tokio::test]
 async fn test_create_and_get_thread_assistant() {
-    let (app, data_dir) = mock_app_with_temp_data_dir();
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
     // Create a thread
     let thread = json!({
         "object": "thread",
@@ -131,3 +142,704 @@ async fn test_create_and_get_thread_assistant() {
     // Clean up
     let _ = fs::remove_dir_all(data_dir);
 }
+
+#[test]
+fn test_write_messages_to_file_failure_keeps_original() {
+    let dir = std::env::temp_dir().join(format!("jan-messages-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("messages.jsonl");
+
+    let original = vec![json!({ "id": "1", "role": "user" })];
+    write_messages_to_file(&original, &path).unwrap();
+    let before = fs::read_to_string(&path).unwrap();
+    assert!(!dir.join("messages.jsonl.tmp").exists());
+
+    // A directory in place of the temp file makes the write fail before the rename
+    fs::create_dir(dir.join("messages.jsonl.tmp")).unwrap();
+    let updated = vec![json!({ "id": "1", "role": "user" }), json!({ "id": "2" })];
+    assert!(write_messages_to_file(&updated, &path).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), before);
+
+    // Clean up
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn test_append_message_to_new_thread() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread_id = uuid::Uuid::new_v4().to_string();
+
+    // No thread directory exists yet
+    for id in ["1", "2"] {
+        append_message_to_file(app.handle().clone(), &thread_id, &json!({ "id": id }))
+            .await
+            .unwrap();
+    }
+
+    let messages = read_messages_from_file(app.handle().clone(), &thread_id).unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[1]["id"], "2");
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_read_messages_skips_corrupted_lines() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread_id = uuid::Uuid::new_v4().to_string();
+    append_message_to_file(app.handle().clone(), &thread_id, &json!({ "id": "1" }))
+        .await
+        .unwrap();
+
+    // Simulate a half-written line followed by a good one
+    let path = super::utils::get_messages_path(app.handle().clone(), &thread_id);
+    let mut contents = fs::read_to_string(&path).unwrap();
+    contents.push_str("{\"id\": \"2\", \"content\n{\"id\": \"3\"}\n");
+    fs::write(&path, contents).unwrap();
+
+    assert!(read_messages_from_file(app.handle().clone(), &thread_id).is_err());
+
+    let messages = read_messages_lenient(app.handle().clone(), &thread_id).unwrap();
+    let ids: Vec<&str> = messages.iter().map(|m| m["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec!["1", "3"]);
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_compact_thread() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread_id = create_thread_with_messages(&app, &["1", "2"]).await;
+
+    // Already compact
+    assert_eq!(
+        helpers::compact_thread(app.handle().clone(), &thread_id)
+            .await
+            .unwrap(),
+        0
+    );
+
+    let path = super::utils::get_messages_path(app.handle().clone(), &thread_id);
+    let mut contents = fs::read_to_string(&path).unwrap();
+    let compact_len = contents.len() as u64 + 11; // with message 3 below
+    contents.push_str("{\"id\": \"broken\n\n{\"id\":\"3\"}\n");
+    fs::write(&path, contents).unwrap();
+
+    let reclaimed = helpers::compact_thread(app.handle().clone(), &thread_id)
+        .await
+        .unwrap();
+    assert_eq!(reclaimed, 16);
+    assert_eq!(fs::metadata(&path).unwrap().len(), compact_len);
+    assert_eq!(message_ids(&app, &thread_id), vec!["1", "2", "3"]);
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_archive_thread() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread_id = create_thread_with_messages(&app, &["1", "2", "3"]).await;
+    let path = super::utils::get_messages_path(app.handle().clone(), &thread_id);
+    let archive_path = path.with_file_name("messages.jsonl.gz");
+
+    helpers::archive_thread(app.handle().clone(), &thread_id)
+        .await
+        .unwrap();
+    assert!(!path.exists());
+    assert!(archive_path.exists());
+    assert_eq!(message_ids(&app, &thread_id), vec!["1", "2", "3"]);
+
+    // New messages go to an uncompressed messages.jsonl after the archive
+    append_message_to_file(app.handle().clone(), &thread_id, &json!({ "id": "4" }))
+        .await
+        .unwrap();
+    assert!(path.exists());
+    assert_eq!(message_ids(&app, &thread_id), vec!["1", "2", "3", "4"]);
+    let page = read_messages_paginated(app.handle().clone(), &thread_id, 0, 2).unwrap();
+    assert_eq!(page.total, 4);
+    let page = read_messages_before(app.handle().clone(), &thread_id, Some("4"), 2).unwrap();
+    let ids: Vec<&str> = page
+        .messages
+        .iter()
+        .map(|m| m["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["3", "2"]);
+    assert_eq!(page.next_cursor.as_deref(), Some("2"));
+
+    // Rewriting an archived thread keeps it compressed
+    helpers::delete_message(app.handle().clone(), &thread_id, "2")
+        .await
+        .unwrap();
+    assert!(!path.exists());
+    assert!(archive_path.exists());
+    assert_eq!(message_ids(&app, &thread_id), vec!["1", "3", "4"]);
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_interrupted_archive_recovers_without_duplicates() {
+    use std::io::Write;
+
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread_id = create_thread_with_messages(&app, &["1", "2"]).await;
+    let path = super::utils::get_messages_path(app.handle().clone(), &thread_id);
+    let archive_path = path.with_file_name("messages.jsonl.gz");
+    let staged_path = path.with_file_name("messages.jsonl.staged");
+    let next_path = path.with_file_name("messages.jsonl.gz.next");
+    helpers::archive_thread(app.handle().clone(), &thread_id)
+        .await
+        .unwrap();
+
+    // Crash after the live file was staged but before the new archive was written
+    append_message_to_file(app.handle().clone(), &thread_id, &json!({ "id": "3" }))
+        .await
+        .unwrap();
+    fs::rename(&path, &staged_path).unwrap();
+    assert_eq!(message_ids(&app, &thread_id), vec!["1", "2", "3"]);
+
+    // Crash after the new archive was written but before it replaced the old one
+    let mut encoder = flate2::write::GzEncoder::new(
+        fs::File::create(&next_path).unwrap(),
+        flate2::Compression::default(),
+    );
+    for id in ["1", "2", "3"] {
+        writeln!(encoder, "{}", json!({ "id": id })).unwrap();
+    }
+    encoder.finish().unwrap();
+    assert_eq!(message_ids(&app, &thread_id), vec!["1", "2", "3"]);
+
+    append_message_to_file(app.handle().clone(), &thread_id, &json!({ "id": "4" }))
+        .await
+        .unwrap();
+    helpers::archive_thread(app.handle().clone(), &thread_id)
+        .await
+        .unwrap();
+    assert!(archive_path.exists());
+    assert!(!staged_path.exists());
+    assert!(!next_path.exists());
+    assert_eq!(message_ids(&app, &thread_id), vec!["1", "2", "3", "4"]);
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_verify_and_repair_thread() {
+    use super::models::ThreadProblemKind;
+
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread_id = uuid::Uuid::new_v4().to_string();
+    for message in [
+        json!({ "id": "1", "role": "user" }),
+        json!({ "id": "2" }),
+        json!({ "id": "1", "role": "assistant" }),
+        json!({ "id": "3", "role": "assistant" }),
+    ] {
+        append_message_to_file(app.handle().clone(), &thread_id, &message)
+            .await
+            .unwrap();
+    }
+    let path = super::utils::get_messages_path(app.handle().clone(), &thread_id);
+    let mut contents = fs::read_to_string(&path).unwrap();
+    contents.push_str("{\"id\": \"4\", \"ro\n");
+    fs::write(&path, contents).unwrap();
+
+    // Reads that rewrite the file refuse malformed lines and point at the fix
+    let error = read_messages_from_file(app.handle().clone(), &thread_id).unwrap_err();
+    assert!(error.contains("Line 5"), "{error}");
+    assert!(error.contains("repair_thread"), "{error}");
+
+    let report = helpers::verify_thread(app.handle().clone(), &thread_id)
+        .await
+        .unwrap();
+    assert_eq!(report.valid_messages, 2);
+    let problems: Vec<_> = report.problems.iter().map(|p| (p.line, p.kind)).collect();
+    assert_eq!(
+        problems,
+        vec![
+            (2, ThreadProblemKind::MissingField),
+            (3, ThreadProblemKind::DuplicateId),
+            (5, ThreadProblemKind::InvalidJson),
+        ]
+    );
+
+    let report = helpers::repair_thread(app.handle().clone(), &thread_id)
+        .await
+        .unwrap();
+    assert_eq!(report.problems.len(), 3);
+    assert_eq!(message_ids(&app, &thread_id), vec!["1", "3"]);
+    let quarantine = fs::read_to_string(path.with_file_name("messages.jsonl.quarantine")).unwrap();
+    assert_eq!(quarantine.lines().count(), 3);
+
+    let report = helpers::verify_thread(app.handle().clone(), &thread_id)
+        .await
+        .unwrap();
+    assert!(report.problems.is_empty());
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_read_messages_paginated() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread_id = uuid::Uuid::new_v4().to_string();
+    for id in 0..10 {
+        append_message_to_file(app.handle().clone(), &thread_id, &json!({ "id": id }))
+            .await
+            .unwrap();
+    }
+
+    let ids = |offset, limit| {
+        let page =
+            read_messages_paginated(app.handle().clone(), &thread_id, offset, limit).unwrap();
+        assert_eq!(page.total, 10);
+        page.messages
+            .iter()
+            .map(|m| m["id"].as_u64().unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    // Offsets count from the newest message
+    assert_eq!(ids(0, 3), vec![7, 8, 9]);
+    assert_eq!(ids(3, 3), vec![4, 5, 6]);
+    assert_eq!(ids(8, 5), vec![0, 1]);
+    assert!(ids(20, 5).is_empty());
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_read_messages_before() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let ids: Vec<String> = (0..10).map(|id| id.to_string()).collect();
+    let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+    let thread_id = create_thread_with_messages(&app, &ids).await;
+
+    let page = |before: Option<&str>, limit| {
+        let page = read_messages_before(app.handle().clone(), &thread_id, before, limit).unwrap();
+        let ids: Vec<String> = page
+            .messages
+            .iter()
+            .map(|m| m["id"].as_str().unwrap().to_string())
+            .collect();
+        (ids, page.next_cursor)
+    };
+
+    assert_eq!(
+        page(None, 4),
+        (
+            vec!["9".into(), "8".into(), "7".into(), "6".into()],
+            Some("6".into())
+        )
+    );
+    assert_eq!(
+        page(Some("6"), 4),
+        (
+            vec!["5".into(), "4".into(), "3".into(), "2".into()],
+            Some("2".into())
+        )
+    );
+    assert_eq!(page(Some("2"), 4), (vec!["1".into(), "0".into()], None));
+    assert_eq!(page(Some("0"), 4), (vec![], None));
+    assert!(read_messages_before(app.handle().clone(), &thread_id, Some("42"), 4).is_err());
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_search_messages() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread_id = uuid::Uuid::new_v4().to_string();
+    let text_message = |id: &str, text: &str| {
+        json!({
+            "id": id,
+            "role": "user",
+            "content": [{ "type": "text", "text": { "value": text, "annotations": [] } }]
+        })
+    };
+    for message in [
+        text_message("1", "How do I install Rust?"),
+        text_message("2", "Use rustup, then run cargo build."),
+        text_message("3", "Thanks!"),
+    ] {
+        append_message_to_file(app.handle().clone(), &thread_id, &message)
+            .await
+            .unwrap();
+    }
+
+    let results = search_messages(app.handle().clone(), &thread_id, "RUST").unwrap();
+    let ids: Vec<&str> = results.iter().map(|r| r.message_id.as_str()).collect();
+    assert_eq!(ids, vec!["1", "2"]);
+    assert_eq!(results[0].offset, 17);
+    assert_eq!(results[1].snippet, "Use rustup, then run cargo build.");
+
+    assert!(search_messages(app.handle().clone(), &thread_id, "python")
+        .unwrap()
+        .is_empty());
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+async fn create_thread_with_messages(app: &App<MockRuntime>, ids: &[&str]) -> String {
+    let thread_id = uuid::Uuid::new_v4().to_string();
+    for id in ids {
+        append_message_to_file(app.handle().clone(), &thread_id, &json!({ "id": id }))
+            .await
+            .unwrap();
+    }
+    thread_id
+}
+
+fn message_ids(app: &App<MockRuntime>, thread_id: &str) -> Vec<String> {
+    read_messages_from_file(app.handle().clone(), thread_id)
+        .unwrap()
+        .iter()
+        .map(|m| m["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_delete_last_message() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread_id = create_thread_with_messages(&app, &["1", "2", "3"]).await;
+
+    helpers::delete_message(app.handle().clone(), &thread_id, "3")
+        .await
+        .unwrap();
+    assert_eq!(message_ids(&app, &thread_id), vec!["1", "2"]);
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_delete_middle_message() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread_id = create_thread_with_messages(&app, &["1", "2", "3"]).await;
+
+    helpers::delete_message(app.handle().clone(), &thread_id, "2")
+        .await
+        .unwrap();
+    assert_eq!(message_ids(&app, &thread_id), vec!["1", "3"]);
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_update_nonexistent_message() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread_id = create_thread_with_messages(&app, &["1", "2"]).await;
+
+    let error = helpers::update_message(
+        app.handle().clone(),
+        &thread_id,
+        "missing",
+        json!({ "id": "missing" }),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        format!("Message missing not found in thread {}", thread_id)
+    );
+    assert_eq!(message_ids(&app, &thread_id), vec!["1", "2"]);
+
+    // The commands pass the error through
+    let message = json!({ "id": "missing", "thread_id": thread_id });
+    assert!(modify_message(app.handle().clone(), message).await.is_err());
+    assert!(delete_message(
+        app.handle().clone(),
+        thread_id.clone(),
+        "missing".to_string(),
+    )
+    .await
+    .is_err());
+    assert_eq!(message_ids(&app, &thread_id), vec!["1", "2"]);
+
+    helpers::update_message(
+        app.handle().clone(),
+        &thread_id,
+        "2",
+        json!({ "id": "2", "role": "assistant" }),
+    )
+    .await
+    .unwrap();
+    let messages = read_messages_from_file(app.handle().clone(), &thread_id).unwrap();
+    assert_eq!(messages[1]["role"], "assistant");
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_export_thread_markdown() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread = json!({
+        "object": "thread",
+        "title": "Export Thread",
+        "assistants": [{ "assistant_name": "Jan", "model": { "id": "llama3.2-1b" } }],
+        "created": 1,
+        "updated": 1,
+        "metadata": null
+    });
+    let created = create_thread(app.handle().clone(), thread).await.unwrap();
+    let thread_id = created["id"].as_str().unwrap().to_string();
+
+    let text_part =
+        |text: &str| json!({ "type": "text", "text": { "value": text, "annotations": [] } });
+    for (role, content) in [
+        ("user", vec![text_part("Print hello"), text_part("in Rust")]),
+        (
+            "assistant",
+            vec![text_part("```rust\nprintln!(\"hello\");\n```")],
+        ),
+    ] {
+        let message = json!({ "thread_id": thread_id, "role": role, "content": content });
+        create_message(app.handle().clone(), message).await.unwrap();
+    }
+
+    let markdown = helpers::export_thread_markdown(app.handle().clone(), &thread_id).unwrap();
+    assert!(markdown.starts_with("# Export Thread\n"));
+    assert!(markdown.contains("- Model: llama3.2-1b\n"));
+    assert!(markdown.contains("**User**\n\nPrint hello\nin Rust\n"));
+    assert!(markdown.contains("**Assistant**\n\n```rust\nprintln!(\"hello\");\n```\n"));
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_thread_metadata_backup() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread = json!({
+        "object": "thread",
+        "title": "Before",
+        "assistants": [],
+        "created": 1,
+        "updated": 1,
+        "metadata": null
+    });
+    let mut created = create_thread(app.handle().clone(), thread).await.unwrap();
+    let thread_id = created["id"].as_str().unwrap().to_string();
+    let metadata_path = super::utils::get_thread_metadata_path(app.handle().clone(), &thread_id);
+    let before = fs::read_to_string(&metadata_path).unwrap();
+
+    created["title"] = json!("After");
+    modify_thread(app.handle().clone(), created).await.unwrap();
+
+    // The backup holds the pre-update content
+    let backup_path = metadata_path.with_file_name("thread.json.bak");
+    assert_eq!(fs::read_to_string(&backup_path).unwrap(), before);
+    assert!(fs::read_to_string(&metadata_path)
+        .unwrap()
+        .contains("After"));
+
+    helpers::restore_thread_metadata(app.handle().clone(), &thread_id)
+        .await
+        .unwrap();
+    assert_eq!(fs::read_to_string(&metadata_path).unwrap(), before);
+    assert!(fs::read_to_string(&backup_path).unwrap().contains("After"));
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_concurrent_thread_metadata_updates() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread = json!({
+        "object": "thread",
+        "title": "Concurrent",
+        "assistants": [],
+        "created": 1,
+        "updated": 1,
+        "metadata": null
+    });
+    let created = create_thread(app.handle().clone(), thread).await.unwrap();
+    let thread_id = created["id"].as_str().unwrap().to_string();
+
+    let mut tasks = Vec::new();
+    for i in 0..10 {
+        let app_handle = app.handle().clone();
+        let thread_id = thread_id.clone();
+        tasks.push(tokio::spawn(async move {
+            create_thread_assistant(app_handle, thread_id, json!({ "id": i.to_string() })).await
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    // No update was lost or interleaved with another
+    let assistant = get_thread_assistant(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap();
+    assert!(assistant.get("id").is_some());
+    let metadata_path = super::utils::get_thread_metadata_path(app.handle().clone(), &thread_id);
+    let thread = helpers::read_thread_metadata(&metadata_path).unwrap();
+    assert_eq!(thread["assistants"].as_array().unwrap().len(), 10);
+
+    // Only the backup survived, e.g. after a crash: it is read in its place
+    fs::rename(
+        &metadata_path,
+        metadata_path.with_file_name("thread.json.bak"),
+    )
+    .unwrap();
+    let threads = list_threads(app.handle().clone()).await.unwrap();
+    assert!(threads.iter().any(|t| t["id"] == json!(thread_id)));
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_thread_index() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let thread = json!({
+        "object": "thread",
+        "title": "Indexed Thread",
+        "assistants": [],
+        "created": 1,
+        "updated": 2,
+        "metadata": null
+    });
+    let mut created = create_thread(app.handle().clone(), thread).await.unwrap();
+    let thread_id = created["id"].as_str().unwrap().to_string();
+    for id in ["1", "2"] {
+        append_message_to_file(app.handle().clone(), &thread_id, &json!({ "id": id }))
+            .await
+            .unwrap();
+    }
+    created["title"] = json!("Renamed Thread");
+    created["updated"] = json!(3);
+    modify_thread(app.handle().clone(), created).await.unwrap();
+
+    let expected = ThreadIndexEntry {
+        id: thread_id.clone(),
+        title: "Renamed Thread".to_string(),
+        updated: 3,
+        message_count: 2,
+    };
+    let index = helpers::read_thread_index(app.handle().clone()).unwrap();
+    assert_eq!(index.get(&thread_id), Some(&expected));
+
+    // Rebuilding from the thread folders gives the same entry
+    fs::remove_file(super::utils::get_thread_index_path(app.handle().clone())).unwrap();
+    let rebuilt = helpers::rebuild_thread_index(app.handle().clone()).unwrap();
+    assert!(rebuilt.contains(&expected));
+
+    // An unreadable index doesn't fail writes, and listing rebuilds it
+    let index_path = super::utils::get_thread_index_path(app.handle().clone());
+    fs::write(&index_path, "{ not json").unwrap();
+    append_message_to_file(app.handle().clone(), &thread_id, &json!({ "id": "3" }))
+        .await
+        .unwrap();
+    fs::write(&index_path, "{ not json").unwrap();
+    let threads = list_threads(app.handle().clone()).await.unwrap();
+    assert!(threads.iter().any(|t| t["id"] == json!(thread_id)));
+    let index = helpers::read_thread_index(app.handle().clone()).unwrap();
+    assert_eq!(index[&thread_id].message_count, 3);
+
+    delete_thread(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap();
+    let index = helpers::read_thread_index(app.handle().clone()).unwrap();
+    assert!(!index.contains_key(&thread_id));
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_lock_thread_timeout() {
+    use std::time::Duration;
+
+    let thread_id = "lock-timeout-test";
+    let held = helpers::lock_thread(thread_id, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    // A stuck writer keeps the lock, the next caller gets a busy error instead of hanging
+    let result = helpers::lock_thread(thread_id, Duration::from_millis(50)).await;
+    assert!(matches!(
+        result,
+        Err(ThreadLockError::Busy { ref thread_id, timeout_ms: 50 }) if thread_id == "lock-timeout-test"
+    ));
+    // Other threads are not affected
+    assert!(
+        helpers::lock_thread("other-thread", Duration::from_millis(50))
+            .await
+            .is_ok()
+    );
+
+    drop(held);
+    assert!(helpers::lock_thread(thread_id, Duration::from_millis(50))
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_list_threads_with_preview() {
+    let (app, data_dir, _data_lock) = mock_app_with_temp_data_dir().await;
+    let create = |title: &str, updated: i64| {
+        create_thread(
+            app.handle().clone(),
+            json!({
+                "object": "thread",
+                "title": title,
+                "assistants": [],
+                "created": updated,
+                "updated": updated,
+                "metadata": null
+            }),
+        )
+    };
+    let older = create("Older", 10).await.unwrap();
+    let newer = create("Newer", 20).await.unwrap();
+    let older_id = older["id"].as_str().unwrap().to_string();
+    let newer_id = newer["id"].as_str().unwrap().to_string();
+
+    for (id, role, text) in [("1", "user", "Hello"), ("2", "assistant", "Hi there")] {
+        let message = json!({
+            "id": id,
+            "thread_id": older_id,
+            "role": role,
+            "content": [{"type": "text", "text": {"value": text, "annotations": []}}]
+        });
+        append_message_to_file(app.handle().clone(), &older_id, &message)
+            .await
+            .unwrap();
+    }
+    // A thread with corrupt metadata is skipped
+    let corrupt_dir = super::utils::get_thread_dir(app.handle().clone(), "corrupt-preview-thread");
+    fs::create_dir_all(&corrupt_dir).unwrap();
+    fs::write(corrupt_dir.join("thread.json"), "{not json").unwrap();
+
+    let previews = helpers::list_threads_with_preview(app.handle().clone())
+        .await
+        .unwrap();
+    let ours: Vec<_> = previews
+        .iter()
+        .filter(|p| p.id == older_id || p.id == newer_id)
+        .collect();
+    assert_eq!(ours.len(), 2);
+    assert_eq!(ours[0].id, newer_id);
+    assert_eq!(ours[0].last_message, None);
+    assert_eq!(ours[1].title, "Older");
+    assert_eq!(ours[1].last_message.as_deref(), Some("Hi there"));
+    assert_eq!(ours[1].last_message_role.as_deref(), Some("assistant"));
+    assert!(!previews.iter().any(|p| p.id == "corrupt-preview-thread"));
+
+    // Clean up
+    let _ = fs::remove_dir_all(data_dir);
+}
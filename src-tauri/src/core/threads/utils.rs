@@ -2,7 +2,7 @@ use std::fs;
 use std::path::PathBuf;
 use tauri::Runtime;
 
-use super::constants::{MESSAGES_FILE, THREADS_DIR, THREADS_FILE};
+use super::constants::{MESSAGES_FILE, THREADS_DIR, THREADS_FILE, THREADS_INDEX_FILE};
 use crate::core::app::commands::get_jan_data_folder_path;
 
 pub fn get_data_dir<R: Runtime>(app_handle: tauri::AppHandle<R>) -> PathBuf {
@@ -24,6 +24,10 @@ pub fn get_messages_path<R: Runtime>(app_handle: tauri::AppHandle<R>, thread_id:
     get_thread_dir(app_handle, thread_id).join(MESSAGES_FILE)
 }
 
+pub fn get_thread_index_path<R: Runtime>(app_handle: tauri::AppHandle<R>) -> PathBuf {
+    get_data_dir(app_handle).join(THREADS_INDEX_FILE)
+}
+
 pub fn ensure_data_dirs<R: Runtime>(app_handle: tauri::AppHandle<R>) -> Result<(), String> {
     let data_dir = get_data_dir(app_handle.clone());
     if !data_dir.exists() {
@@ -7,7 +7,10 @@ use core::{
     state::AppState,
 };
 use jan_utils::generate_app_token;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{atomic::AtomicBool, Arc},
+};
 use tauri::{Emitter, Manager, RunEvent};
 use tokio::sync::Mutex;
 
@@ -69,23 +72,51 @@ pub fn run() {
             // Server commands removed for mobile/remote-only build
             // MCP commands
             core::mcp::commands::get_tools,
+            core::mcp::commands::list_all_mcp_tools,
+            core::mcp::commands::get_cached_mcp_tools,
+            core::mcp::commands::get_mcp_tool_schemas,
+            core::mcp::commands::get_mcp_tool_schema,
             core::mcp::commands::call_tool,
+            core::mcp::commands::cancel_mcp_tool_call,
+            core::mcp::commands::list_mcp_resources,
+            core::mcp::commands::list_mcp_prompts,
             core::mcp::commands::restart_mcp_servers,
+            core::mcp::commands::restart_all_mcp_servers,
+            core::mcp::commands::pause_mcp_monitoring,
+            core::mcp::commands::resume_mcp_monitoring,
+            core::mcp::commands::restart_mcp_server,
             core::mcp::commands::get_connected_servers,
+            core::mcp::commands::get_mcp_server_status,
+            core::mcp::commands::get_mcp_events,
+            core::mcp::commands::get_mcp_process_stats,
+            core::mcp::commands::get_mcp_server_log,
+            core::mcp::commands::test_mcp_server,
             core::mcp::commands::save_mcp_configs,
             core::mcp::commands::get_mcp_configs,
             core::mcp::commands::activate_mcp_server,
             core::mcp::commands::deactivate_mcp_server,
+            core::mcp::commands::stop_mcp_server,
             core::mcp::commands::reset_mcp_restart_count,
             // Threads
             core::threads::commands::list_threads,
+            core::threads::commands::list_threads_with_preview,
             core::threads::commands::create_thread,
             core::threads::commands::modify_thread,
             core::threads::commands::delete_thread,
+            core::threads::commands::restore_thread_metadata,
+            core::threads::commands::rebuild_thread_index,
             core::threads::commands::list_messages,
+            core::threads::commands::list_messages_paginated,
+            core::threads::commands::list_messages_before,
+            core::threads::commands::search_messages,
+            core::threads::commands::export_thread_markdown,
             core::threads::commands::create_message,
             core::threads::commands::modify_message,
             core::threads::commands::delete_message,
+            core::threads::commands::compact_thread,
+            core::threads::commands::archive_thread,
+            core::threads::commands::verify_thread,
+            core::threads::commands::repair_thread,
             core::threads::commands::get_thread_assistant,
             core::threads::commands::create_thread_assistant,
             core::threads::commands::modify_thread_assistant,
@@ -100,6 +131,14 @@ pub fn run() {
             mcp_restart_counts: Arc::new(Mutex::new(HashMap::new())),
             mcp_active_servers: Arc::new(Mutex::new(HashMap::new())),
             mcp_successfully_connected: Arc::new(Mutex::new(HashMap::new())),
+            mcp_server_pids: Arc::new(Mutex::new(HashMap::new())),
+            mcp_tool_cache: Arc::new(Mutex::new(HashMap::new())),
+            mcp_monitor_tasks: Arc::new(Mutex::new(HashMap::new())),
+            mcp_events: Arc::new(Mutex::new(VecDeque::new())),
+            mcp_monitoring_paused: Arc::new(AtomicBool::new(false)),
+            mcp_tool_calls: Arc::new(Mutex::new(HashMap::new())),
+            mcp_stopped_servers: Arc::new(Mutex::new(HashSet::new())),
+            mcp_saved_config_hash: Arc::new(Mutex::new(None)),
             server_handle: Arc::new(Mutex::new(None)),
         })
         .setup(|app| {
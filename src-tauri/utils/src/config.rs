@@ -21,3 +21,100 @@ pub fn extract_active_status(config: &Value) -> Option<bool> {
     let active = obj.get("active")?.as_bool()?;
     Some(active)
 }
+
+/// Blanks out `//` and `/* */` comments and drops trailing commas before `}` or `]`,
+/// leaving strict JSON as is. Removed characters become spaces and newlines are kept,
+/// so parse errors still point at the right line and column.
+pub fn strip_json_comments(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    let mut escaped = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                output.push(' ');
+                while let Some(&next) = chars.peek() {
+                    if next == '\n' {
+                        break;
+                    }
+                    output.push(' ');
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                output.push(' ');
+                output.push(' ');
+                chars.next();
+                let mut last = '\0';
+                for next in chars.by_ref() {
+                    output.push(if next == '\n' { '\n' } else { ' ' });
+                    if last == '*' && next == '/' {
+                        break;
+                    }
+                    last = next;
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+
+    remove_trailing_commas(&output)
+}
+
+/// Replaces commas that follow a value and are followed only by whitespace and a closing
+/// bracket with a space. `[,]` and `{,}` stay invalid.
+fn remove_trailing_commas(content: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut output = String::with_capacity(content.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            let previous = output.trim_end().chars().next_back();
+            let after_value = !matches!(previous, None | Some('[') | Some('{') | Some(','));
+            if after_value && matches!(next, Some('}') | Some(']')) {
+                output.push(' ');
+                continue;
+            }
+        }
+        output.push(c);
+    }
+    output
+}
+
+/// Parses JSON that may contain comments and trailing commas, as written by hand in
+/// config files like mcp_config.json
+pub fn parse_lenient_json(content: &str) -> Result<Value, serde_json::Error> {
+    serde_json::from_str(&strip_json_comments(content))
+}
+
+#[cfg(test)]
+mod tests;
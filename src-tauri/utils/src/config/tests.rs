@@ -0,0 +1,64 @@
+use super::*;
+use serde_json::json;
+
+#[test]
+fn test_strict_json_is_unchanged() {
+    let content = r#"{"mcpServers": {"fetch": {"command": "uvx", "args": ["a,]", "// not a comment", "/* nor this */"], "env": {"QUOTE": "\"//\""}}}}"#;
+    assert_eq!(strip_json_comments(content), content);
+    assert_eq!(
+        parse_lenient_json(content).unwrap(),
+        serde_json::from_str::<Value>(content).unwrap()
+    );
+}
+
+#[test]
+fn test_commented_out_servers() {
+    let content = r#"{
+  "mcpServers": {
+    // "browsermcp": { "command": "npx", "args": ["@browsermcp/mcp"] },
+    /* "serper": {
+      "command": "npx",
+      "args": ["-y", "serper-search-scrape-mcp-server"]
+    }, */
+    "fetch": { "command": "uvx", "args": ["mcp-server-fetch"] } // enabled
+  }
+}"#;
+    assert_eq!(
+        parse_lenient_json(content).unwrap(),
+        json!({ "mcpServers": { "fetch": { "command": "uvx", "args": ["mcp-server-fetch"] } } })
+    );
+}
+
+#[test]
+fn test_trailing_commas() {
+    let content = r#"{
+  "mcpServers": {
+    "fetch": {
+      "command": "uvx",
+      "args": ["mcp-server-fetch",],
+      "active": true, // comment after the last comma
+    },
+  },
+}"#;
+    assert_eq!(
+        parse_lenient_json(content).unwrap(),
+        json!({ "mcpServers": { "fetch": { "command": "uvx", "args": ["mcp-server-fetch"], "active": true } } })
+    );
+}
+
+#[test]
+fn test_errors_keep_their_position() {
+    let content = "{\n  /* two\n  lines */ \"fetch\": nul\n}";
+    let blanked = "{\n        \n           \"fetch\": nul\n}";
+    assert_eq!(strip_json_comments(content), blanked);
+    let error = parse_lenient_json(content).unwrap_err();
+    let strict_error = serde_json::from_str::<Value>(blanked).unwrap_err();
+    assert_eq!(
+        (error.line(), error.column()),
+        (strict_error.line(), strict_error.column())
+    );
+
+    assert!(parse_lenient_json("{ \"a\": 1,, }").is_err());
+    assert!(parse_lenient_json("[,]").is_err());
+    assert!(parse_lenient_json("{,}").is_err());
+}
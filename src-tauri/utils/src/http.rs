@@ -1,50 +1,121 @@
+use std::net::IpAddr;
+
 /// Checks if header name is a CORS-related header
 pub fn is_cors_header(header_name: &str) -> bool {
     let header_lower = header_name.to_lowercase();
     header_lower.starts_with("access-control-")
 }
 
-/// Validates if host is in trusted hosts list
-pub fn is_valid_host(host: &str, trusted_hosts: &[Vec<String>]) -> bool {
-    if host.is_empty() {
-        return false;
-    }
+const DEFAULT_TRUSTED_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "0.0.0.0"];
 
-    let host_without_port = if host.starts_with('[') {
+/// Strips the port from a `host[:port]` or `[ipv6]:port` string
+fn strip_port(host: &str) -> &str {
+    if host.starts_with('[') {
         host.split(']')
             .next()
             .unwrap_or(host)
             .trim_start_matches('[')
+    } else if host.matches(':').count() > 1 {
+        // bare IPv6 address, it has no port
+        host
     } else {
         host.split(':').next().unwrap_or(host)
-    };
-    let default_valid_hosts = ["localhost", "127.0.0.1", "0.0.0.0"];
-
-    if default_valid_hosts
-        .iter()
-        .any(|&valid| host_without_port.to_lowercase() == valid.to_lowercase())
-    {
-        return true;
     }
+}
 
-    trusted_hosts.iter().flatten().any(|valid| {
-        let host_lower = host.to_lowercase();
-        let valid_lower = valid.to_lowercase();
+/// A single trusted host entry, parsed from its configured string
+#[derive(Debug, Clone, PartialEq)]
+enum HostPattern {
+    /// `example.com`, `192.168.1.10` or `[::1]:1337`, the port is ignored
+    Exact(String),
+    /// `*.example.com`, any subdomain of `example.com` but not `example.com` itself
+    Wildcard(String),
+    /// `10.0.0.0/8` or `fd00::/8`
+    Cidr(IpAddr, u8),
+}
 
-        if host_lower == valid_lower {
-            return true;
+impl HostPattern {
+    fn parse(pattern: &str) -> Self {
+        let pattern = pattern.trim().to_lowercase();
+        if let Some(domain) = pattern.strip_prefix("*.") {
+            return HostPattern::Wildcard(format!(".{}", domain));
+        }
+        if let Some((addr, prefix)) = pattern.split_once('/') {
+            if let (Ok(addr), Ok(prefix)) = (addr.parse::<IpAddr>(), prefix.parse::<u8>()) {
+                let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+                if prefix <= max_prefix {
+                    return HostPattern::Cidr(addr, prefix);
+                }
+            }
         }
+        HostPattern::Exact(strip_port(&pattern).to_string())
+    }
 
-        let valid_without_port = if valid.starts_with('[') {
-            valid
-                .split(']')
-                .next()
-                .unwrap_or(valid)
-                .trim_start_matches('[')
-        } else {
-            valid.split(':').next().unwrap_or(valid)
-        };
+    /// `host` is lowercase and without port
+    fn matches(&self, host: &str) -> bool {
+        match self {
+            HostPattern::Exact(exact) => host == exact,
+            HostPattern::Wildcard(suffix) => host.len() > suffix.len() && host.ends_with(suffix),
+            HostPattern::Cidr(network, prefix) => host
+                .parse::<IpAddr>()
+                .is_ok_and(|addr| ip_in_network(addr, *network, *prefix)),
+        }
+    }
+}
 
-        host_without_port.to_lowercase() == valid_without_port.to_lowercase()
-    })
+fn ip_in_network(addr: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    match (addr, network) {
+        (IpAddr::V4(addr), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            u32::from(addr) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(addr), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            u128::from(addr) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
 }
+
+/// Trusted hosts parsed once, e.g. when the API server starts, to validate the
+/// `Host` header of each request without re-parsing the configuration.
+///
+/// Entries can be exact hosts (`example.com`, `192.168.1.10:1337`), wildcard
+/// subdomains (`*.example.com`) or IP ranges in CIDR notation (`10.0.0.0/8`).
+/// `localhost`, `127.0.0.1` and `0.0.0.0` are always trusted.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedHostMatcher {
+    patterns: Vec<HostPattern>,
+}
+
+impl TrustedHostMatcher {
+    pub fn new(trusted_hosts: &[Vec<String>]) -> Self {
+        let patterns = DEFAULT_TRUSTED_HOSTS
+            .iter()
+            .map(|host| host.to_string())
+            .chain(trusted_hosts.iter().flatten().cloned())
+            .filter(|host| !host.trim().is_empty())
+            .map(|host| HostPattern::parse(&host))
+            .collect();
+        Self { patterns }
+    }
+
+    /// Whether `host`, as sent in the `Host` header, is trusted
+    pub fn is_trusted(&self, host: &str) -> bool {
+        if host.is_empty() {
+            return false;
+        }
+        let host = strip_port(host).to_lowercase();
+        self.patterns.iter().any(|pattern| pattern.matches(&host))
+    }
+}
+
+/// Validates if host is in trusted hosts list
+///
+/// Parses `trusted_hosts` on every call, servers should build a `TrustedHostMatcher` once.
+pub fn is_valid_host(host: &str, trusted_hosts: &[Vec<String>]) -> bool {
+    TrustedHostMatcher::new(trusted_hosts).is_trusted(host)
+}
+
+#[cfg(test)]
+mod tests;
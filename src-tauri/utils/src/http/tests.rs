@@ -0,0 +1,62 @@
+use super::*;
+
+fn matcher(hosts: &[&str]) -> TrustedHostMatcher {
+    TrustedHostMatcher::new(&[hosts.iter().map(|host| host.to_string()).collect()])
+}
+
+#[test]
+fn test_exact_hosts() {
+    let matcher = matcher(&["Jan.example.com", "192.168.1.10:1337", "[::1]:1337"]);
+
+    assert!(matcher.is_trusted("localhost:1337"));
+    assert!(matcher.is_trusted("127.0.0.1"));
+    assert!(matcher.is_trusted("jan.example.com"));
+    assert!(matcher.is_trusted("JAN.example.com:8080"));
+    assert!(matcher.is_trusted("192.168.1.10:1337"));
+    assert!(matcher.is_trusted("[::1]:1337"));
+
+    assert!(!matcher.is_trusted(""));
+    assert!(!matcher.is_trusted("example.com"));
+    assert!(!matcher.is_trusted("api.jan.example.com"));
+    assert!(!matcher.is_trusted("192.168.1.11"));
+}
+
+#[test]
+fn test_wildcard_hosts() {
+    let matcher = matcher(&["*.mycompany.com"]);
+
+    assert!(matcher.is_trusted("jan.mycompany.com"));
+    assert!(matcher.is_trusted("a.b.mycompany.com:1337"));
+
+    assert!(!matcher.is_trusted("mycompany.com"));
+    assert!(!matcher.is_trusted("evilmycompany.com"));
+    assert!(!matcher.is_trusted("mycompany.com.evil.com"));
+}
+
+#[test]
+fn test_cidr_hosts() {
+    let matcher = matcher(&["10.0.0.0/8", "192.168.1.0/24", "fd00::/8"]);
+
+    assert!(matcher.is_trusted("10.1.2.3"));
+    assert!(matcher.is_trusted("192.168.1.200:1337"));
+    assert!(matcher.is_trusted("[fd12:3456::1]:1337"));
+
+    assert!(!matcher.is_trusted("11.0.0.1"));
+    assert!(!matcher.is_trusted("192.168.2.1"));
+    assert!(!matcher.is_trusted("[fe80::1]"));
+    assert!(!matcher.is_trusted("10.example.com"));
+}
+
+#[test]
+fn test_is_valid_host_flattens_groups() {
+    let trusted_hosts = vec![
+        vec!["jan.example.com".to_string()],
+        vec!["*.mycompany.com".to_string(), "10.0.0.0/8".to_string()],
+    ];
+
+    assert!(is_valid_host("jan.example.com", &trusted_hosts));
+    assert!(is_valid_host("api.mycompany.com", &trusted_hosts));
+    assert!(is_valid_host("10.0.0.5:1337", &trusted_hosts));
+    assert!(!is_valid_host("example.org", &trusted_hosts));
+    assert!(!is_valid_host("", &trusted_hosts));
+}